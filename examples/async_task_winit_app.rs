@@ -0,0 +1,115 @@
+// This example shows how to run async tasks with the app's task executor. A file is read on a
+// background thread, and then decoded into a skia image on the main thread
+
+use skulpin::skia_safe;
+use skulpin::LogicalSize;
+
+use skulpin::app::AppBuilder;
+use skulpin::app::AppUpdateArgs;
+use skulpin::app::AppDrawArgs;
+use skulpin::app::AppError;
+use skulpin::app::AppHandler;
+use skulpin::app::TaskHandle;
+use skulpin::app::VirtualKeyCode;
+
+fn main() {
+    // Setup logging
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Debug)
+        .init();
+
+    AppBuilder::new()
+        .inner_size(LogicalSize::new(900, 600))
+        .run(ExampleApp::new());
+}
+
+// Reads the file on another thread, then decodes it on the main thread. skia_safe::Image can't be
+// sent between threads, but this future doesn't need to be Send since it runs on the main thread
+async fn load_image(path: &'static str) -> Option<skia_safe::Image> {
+    let bytes = skulpin::app::run_on_thread(move || std::fs::read(path))
+        .await
+        .ok()?;
+
+    skia_safe::Image::from_encoded(skia_safe::Data::new_copy(&bytes))
+}
+
+struct ExampleApp {
+    image_task: Option<TaskHandle<Option<skia_safe::Image>>>,
+    image: Option<skia_safe::Image>,
+}
+
+impl ExampleApp {
+    pub fn new() -> Self {
+        ExampleApp {
+            image_task: None,
+            image: None,
+        }
+    }
+}
+
+impl AppHandler for ExampleApp {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+    ) {
+        let input_state = update_args.input_state;
+        let app_control = update_args.app_control;
+        let task_executor = update_args.task_executor;
+
+        if input_state.is_key_down(VirtualKeyCode::Escape) {
+            app_control.enqueue_terminate_process();
+        }
+
+        // Start loading the image on the first update
+        if self.image_task.is_none() && self.image.is_none() {
+            self.image_task = Some(task_executor.spawn(load_image("screenshot.png")));
+        }
+
+        // Check if the image has finished loading
+        if let Some(image_task) = &self.image_task {
+            if let Some(image) = image_task.try_take() {
+                if image.is_none() {
+                    log::warn!("Failed to load screenshot.png");
+                }
+
+                self.image = image;
+                self.image_task = None;
+            }
+        }
+    }
+
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    ) {
+        let canvas = draw_args.canvas;
+
+        // Generally would want to clear data every time we draw
+        canvas.clear(skia_safe::Color::from_argb(0, 0, 0, 255));
+
+        let mut text_paint =
+            skia_safe::Paint::new(skia_safe::Color4f::new(1.0, 1.0, 0.0, 1.0), None);
+        text_paint.set_anti_alias(true);
+
+        let mut font = skia_safe::Font::default();
+        font.set_size(20.0);
+
+        match &self.image {
+            Some(image) => {
+                let dst = skia_safe::Rect::from_xywh(50.0, 80.0, 800.0, 480.0);
+                canvas.draw_image_rect(image, None, dst, &skia_safe::Paint::default());
+                canvas.draw_str("Loaded screenshot.png", (50, 50), &font, &text_paint);
+            }
+            None => {
+                canvas.draw_str("Loading...", (50, 50), &font, &text_paint);
+            }
+        }
+    }
+
+    fn fatal_error(
+        &mut self,
+        error: &AppError,
+    ) {
+        println!("{}", error);
+    }
+}
@@ -0,0 +1,112 @@
+// This example shows how to send events from other threads to an app. The app is woken up when
+// an event arrives, and it's passed to AppHandler::user_event on the main thread
+
+use skulpin::skia_safe;
+use skulpin::LogicalSize;
+
+use skulpin::app::AppBuilder;
+use skulpin::app::AppControl;
+use skulpin::app::AppUpdateArgs;
+use skulpin::app::AppDrawArgs;
+use skulpin::app::AppError;
+use skulpin::app::AppHandler;
+use skulpin::app::VirtualKeyCode;
+
+// Events sent from the background thread to the app
+enum ExampleEvent {
+    WorkCompleted(u32),
+}
+
+fn main() {
+    // Setup logging
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Debug)
+        .init();
+
+    let mut app_builder =
+        AppBuilder::<ExampleEvent>::with_user_event().inner_size(LogicalSize::new(900, 600));
+
+    // Simulate a worker thread that periodically finishes some work
+    let event_sender = app_builder.event_sender();
+    std::thread::spawn(move || {
+        for i in 1.. {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+
+            // This fails once the app has terminated
+            if event_sender.send(ExampleEvent::WorkCompleted(i)).is_err() {
+                break;
+            }
+        }
+    });
+
+    app_builder.run(ExampleApp::new());
+}
+
+struct ExampleApp {
+    completed_work: Vec<u32>,
+}
+
+impl ExampleApp {
+    pub fn new() -> Self {
+        ExampleApp {
+            completed_work: Vec::default(),
+        }
+    }
+}
+
+impl AppHandler<ExampleEvent> for ExampleApp {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+    ) {
+        let input_state = update_args.input_state;
+        let app_control = update_args.app_control;
+
+        if input_state.is_key_down(VirtualKeyCode::Escape) {
+            app_control.enqueue_terminate_process();
+        }
+    }
+
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    ) {
+        let canvas = draw_args.canvas;
+
+        // Generally would want to clear data every time we draw
+        canvas.clear(skia_safe::Color::from_argb(0, 0, 0, 255));
+
+        let mut text_paint =
+            skia_safe::Paint::new(skia_safe::Color4f::new(1.0, 1.0, 0.0, 1.0), None);
+        text_paint.set_anti_alias(true);
+
+        let mut font = skia_safe::Font::default();
+        font.set_size(20.0);
+
+        for (i, work) in self.completed_work.iter().rev().take(20).enumerate() {
+            canvas.draw_str(
+                format!("Work item {} completed", work),
+                (50, 50 + i as i32 * 25),
+                &font,
+                &text_paint,
+            );
+        }
+    }
+
+    fn fatal_error(
+        &mut self,
+        error: &AppError,
+    ) {
+        println!("{}", error);
+    }
+
+    fn user_event(
+        &mut self,
+        _app_control: &mut AppControl,
+        event: ExampleEvent,
+    ) {
+        match event {
+            ExampleEvent::WorkCompleted(i) => self.completed_work.push(i),
+        }
+    }
+}
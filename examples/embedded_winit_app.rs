@@ -0,0 +1,122 @@
+// This example shows how to drive an AppHandler from an event loop and window that are owned by
+// the caller, rather than letting skulpin create them with AppBuilder
+
+use skulpin::skia_safe;
+use skulpin::winit;
+use skulpin::app::AppHandler;
+use skulpin::app::AppError;
+use skulpin::app::AppRunner;
+use skulpin::app::AppUpdateArgs;
+use skulpin::app::AppDrawArgs;
+use skulpin::app::InputConfig;
+use skulpin::app::VirtualKeyCode;
+
+fn main() {
+    // Setup logging
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Debug)
+        .init();
+
+    // The host application creates the event loop and window
+    let event_loop = winit::event_loop::EventLoop::<()>::with_user_event();
+
+    let window = winit::window::WindowBuilder::new()
+        .with_title("Skulpin")
+        .with_inner_size(winit::dpi::LogicalSize::new(900.0, 600.0))
+        .build(&event_loop)
+        .expect("Failed to create window");
+
+    // The runner creates a renderer for the window and drives the app
+    let app_runner: Result<AppRunner<ExampleApp>, AppError> = AppRunner::new(
+        ExampleApp::new(),
+        &window,
+        InputConfig::default(),
+        skulpin::RendererBuilder::new(),
+    );
+
+    let mut app_runner = match app_runner {
+        Ok(app_runner) => app_runner,
+        Err(e) => {
+            println!("Error during app construction: {:?}", e);
+            return;
+        }
+    };
+
+    event_loop.run(move |event, window_target, control_flow| {
+        // Every event is passed to the runner so that it can track input and window state
+        app_runner.handle_event(&window, &event, window_target);
+
+        match event {
+            winit::event::Event::MainEventsCleared => {
+                // The host application can do its own work here, before or after the app updates
+                app_runner.update(&window);
+                window.request_redraw();
+            }
+            winit::event::Event::RedrawRequested(_window_id) => app_runner.draw(&window),
+            _ => {}
+        }
+
+        if app_runner.should_terminate() {
+            *control_flow = winit::event_loop::ControlFlow::Exit
+        }
+    });
+}
+
+struct ExampleApp {
+    update_count: u64,
+}
+
+impl ExampleApp {
+    pub fn new() -> Self {
+        ExampleApp { update_count: 0 }
+    }
+}
+
+impl AppHandler for ExampleApp {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+    ) {
+        let input_state = update_args.input_state;
+        let app_control = update_args.app_control;
+
+        //
+        // Quit if user hits escape
+        //
+        if input_state.is_key_down(VirtualKeyCode::Escape) {
+            app_control.enqueue_terminate_process();
+        }
+
+        self.update_count += 1;
+    }
+
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    ) {
+        let canvas = draw_args.canvas;
+
+        // Generally would want to clear data every time we draw
+        canvas.clear(skia_safe::Color::from_argb(0, 0, 0, 255));
+
+        let mut text_paint =
+            skia_safe::Paint::new(skia_safe::Color4f::new(1.0, 1.0, 0.0, 1.0), None);
+        text_paint.set_anti_alias(true);
+
+        let mut font = skia_safe::Font::default();
+        font.set_size(20.0);
+        canvas.draw_str(
+            format!("Updates: {}", self.update_count),
+            (50, 50),
+            &font,
+            &text_paint,
+        );
+    }
+
+    fn fatal_error(
+        &mut self,
+        error: &AppError,
+    ) {
+        println!("{}", error);
+    }
+}
@@ -6,12 +6,16 @@ use skulpin::app::AppError;
 use skulpin::app::AppBuilder;
 use skulpin::app::MouseButton;
 use skulpin::app::VirtualKeyCode;
+use skulpin::app::CursorIcon;
+use skulpin::app::FullscreenMode;
 use skulpin::app::PhysicalPosition;
 use skulpin::LogicalSize;
 use skulpin::app::AppUpdateArgs;
 use skulpin::app::AppDrawArgs;
+use skulpin::app::AppControl;
 
 use std::collections::VecDeque;
+use std::path::Path;
 
 use skulpin::winit;
 use winit::dpi::LogicalPosition;
@@ -47,6 +51,9 @@ struct ExampleApp {
     last_fps_text_change: Option<std::time::Instant>,
     fps_text: String,
     previous_clicks: VecDeque<PreviousClick>,
+    is_dragging: bool,
+    fullscreen_mode: FullscreenMode,
+    dropped_file: Option<String>,
 }
 
 impl ExampleApp {
@@ -55,6 +62,9 @@ impl ExampleApp {
             last_fps_text_change: None,
             fps_text: "".to_string(),
             previous_clicks: VecDeque::new(),
+            is_dragging: false,
+            fullscreen_mode: FullscreenMode::Windowed,
+            dropped_file: None,
         }
     }
 }
@@ -77,6 +87,20 @@ impl AppHandler for ExampleApp {
             app_control.enqueue_terminate_process();
         }
 
+        //
+        // Toggle fullscreen if user hits F11
+        //
+        if input_state.is_key_just_down(VirtualKeyCode::F11) {
+            self.fullscreen_mode = match self.fullscreen_mode {
+                FullscreenMode::Windowed => FullscreenMode::Borderless,
+                FullscreenMode::Borderless | FullscreenMode::Exclusive(_) => {
+                    FullscreenMode::Windowed
+                }
+            };
+
+            app_control.set_fullscreen(self.fullscreen_mode);
+        }
+
         //
         // Update FPS once a second
         //
@@ -108,6 +132,21 @@ impl AppHandler for ExampleApp {
 
             self.previous_clicks.push_back(previous_click);
         }
+
+        //
+        // Show a "grabbing" cursor while the mouse is being dragged
+        //
+        let is_dragging = input_state.is_mouse_drag_in_progress(MouseButton::Left);
+        if is_dragging != self.is_dragging {
+            let cursor_icon = if is_dragging {
+                CursorIcon::Grabbing
+            } else {
+                CursorIcon::Default
+            };
+
+            app_control.set_cursor_icon(cursor_icon);
+            self.is_dragging = is_dragging;
+        }
     }
 
     fn draw(
@@ -194,7 +233,12 @@ impl AppHandler for ExampleApp {
         let mut font = skia_safe::Font::default();
         font.set_size(20.0);
         canvas.draw_str(self.fps_text.clone(), (50, 50), &font, &text_paint);
-        canvas.draw_str("Click and drag the mouse", (50, 80), &font, &text_paint);
+        canvas.draw_str(
+            "Click and drag the mouse, F11 toggles fullscreen",
+            (50, 80),
+            &font,
+            &text_paint,
+        );
         canvas.draw_str(
             format!("scale factor: {}", input_state.scale_factor()),
             (50, 110),
@@ -216,6 +260,23 @@ impl AppHandler for ExampleApp {
             &font,
             &text_paint,
         );
+
+        if let Some(dropped_file) = &self.dropped_file {
+            canvas.draw_str(
+                format!("dropped file: {}", dropped_file),
+                (50, 170),
+                &font,
+                &text_paint,
+            );
+        }
+    }
+
+    fn file_dropped(
+        &mut self,
+        _app_control: &mut AppControl,
+        path: &Path,
+    ) {
+        self.dropped_file = Some(path.display().to_string());
     }
 
     fn fatal_error(
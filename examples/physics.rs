@@ -8,8 +8,12 @@ use skulpin::app::AppDrawArgs;
 use skulpin::app::AppError;
 use skulpin::app::AppBuilder;
 use skulpin::app::VirtualKeyCode;
+use skulpin::app::MouseButton;
+use skulpin::app::MouseScrollDelta;
 
 use skulpin::LogicalSize;
+use skulpin::Camera2D;
+use skulpin::CoordinateSystemHelper;
 
 // Used for physics
 type Vector2 = rapier2d::na::Vector2<f32>;
@@ -94,7 +98,23 @@ impl Physics {
         let centery = shift / 2.0;
         let height = 3.0;
 
-        let mut circle_body_handles = vec![];
+        let circle_body_handles = vec![];
+
+        let last_update = std::time::Instant::now();
+
+        let mut physics = Physics {
+            physics_pipeline,
+            gravity,
+            integration_parameters,
+            broad_phase,
+            narrow_phase,
+            rigid_body_set,
+            collider_set,
+            joint_set,
+            circle_body_handles,
+            last_update,
+            accumulated_time: 0.0,
+        };
 
         for i in 0usize..BALL_COUNT {
             for j in 0usize..BALL_COUNT {
@@ -108,34 +128,29 @@ impl Physics {
                 let x = i as f32 * shift - centerx;
                 let y = j as f32 * shift + centery + height;
 
-                let rigid_body = RigidBodyBuilder::new_dynamic().translation(x, y).build();
+                physics.add_ball(x, y);
+            }
+        }
 
-                let rigid_body_handle = rigid_body_set.insert(rigid_body);
+        physics
+    }
 
-                let ball_collider = ColliderBuilder::ball(BALL_RADIUS).density(1.0).build();
+    fn add_ball(
+        &mut self,
+        x: f32,
+        y: f32,
+    ) {
+        let rigid_body = RigidBodyBuilder::new_dynamic().translation(x, y).build();
 
-                // Insert the collider to the body set.
-                collider_set.insert(ball_collider, rigid_body_handle, &mut rigid_body_set);
+        let rigid_body_handle = self.rigid_body_set.insert(rigid_body);
 
-                circle_body_handles.push(rigid_body_handle);
-            }
-        }
+        let ball_collider = ColliderBuilder::ball(BALL_RADIUS).density(1.0).build();
 
-        let last_update = std::time::Instant::now();
+        // Insert the collider to the body set.
+        self.collider_set
+            .insert(ball_collider, rigid_body_handle, &mut self.rigid_body_set);
 
-        Physics {
-            physics_pipeline,
-            gravity,
-            integration_parameters,
-            broad_phase,
-            narrow_phase,
-            rigid_body_set,
-            collider_set,
-            joint_set,
-            circle_body_handles,
-            last_update,
-            accumulated_time: 0.0,
-        }
+        self.circle_body_handles.push(rigid_body_handle);
     }
 
     fn update(&mut self) {
@@ -165,6 +180,19 @@ impl Physics {
     }
 }
 
+// The initial view shows the ground with some space on either side. +Y is up, matching the physics
+// world. The user can pan and zoom from here.
+fn default_camera(coordinate_system_helper: &CoordinateSystemHelper) -> Camera2D {
+    let mut camera = Camera2D::new();
+    camera.set_y_up(true);
+    camera.set_position((0.0, 1.0));
+    camera.set_zoom(
+        coordinate_system_helper.window_logical_size().width as f32
+            / (GROUND_HALF_EXTENTS_WIDTH * 3.0),
+    );
+    camera
+}
+
 fn main() {
     // Setup logging
     env_logger::Builder::from_default_env()
@@ -183,6 +211,9 @@ struct ExampleApp {
     fps_text: String,
     physics: Physics,
     circle_colors: Vec<skia_safe::Paint>,
+
+    // Created on the first update, since it depends on the size of the window
+    camera: Option<Camera2D>,
 }
 
 impl ExampleApp {
@@ -207,6 +238,7 @@ impl ExampleApp {
             fps_text: "".to_string(),
             physics: Physics::new(),
             circle_colors,
+            camera: None,
         }
     }
 }
@@ -244,6 +276,56 @@ impl AppHandler for ExampleApp {
             self.last_fps_text_change = Some(now);
         }
 
+        let coordinate_system_helper = update_args.coordinate_system_helper;
+        let camera = self
+            .camera
+            .get_or_insert_with(|| default_camera(&coordinate_system_helper));
+
+        let mouse_position = input_state.mouse_position();
+        let mouse_position =
+            skia_safe::Point::new(mouse_position.x as f32, mouse_position.y as f32);
+
+        //
+        // Zoom toward the cursor with the mouse wheel, pan by dragging with the right mouse button
+        //
+        let scroll = match input_state.mouse_wheel_delta() {
+            MouseScrollDelta::LineDelta(_, y) => y,
+            MouseScrollDelta::PixelDelta(delta) => delta.y as f32 / 50.0,
+        };
+
+        if scroll != 0.0 {
+            camera.zoom_at(
+                &coordinate_system_helper,
+                mouse_position,
+                1.1f32.powf(scroll),
+            );
+        }
+
+        if let Some(drag) = input_state.mouse_drag_in_progress(MouseButton::Right) {
+            camera.pan_by_screen_delta(
+                &coordinate_system_helper,
+                (
+                    drag.previous_frame_delta.x as f32,
+                    drag.previous_frame_delta.y as f32,
+                ),
+            );
+        }
+
+        if input_state.is_key_just_down(VirtualKeyCode::R) {
+            *camera = default_camera(&coordinate_system_helper);
+        }
+
+        //
+        // Drop a new ball wherever the user clicks
+        //
+        if input_state.is_mouse_just_down(MouseButton::Left) {
+            if let Some(world_position) =
+                camera.screen_to_world(&coordinate_system_helper, mouse_position)
+            {
+                self.physics.add_ball(world_position.x, world_position.y);
+            }
+        }
+
         // Update physics
         self.physics.update();
     }
@@ -255,23 +337,9 @@ impl AppHandler for ExampleApp {
         let coordinate_system_helper = draw_args.coordinate_system_helper;
         let canvas = draw_args.canvas;
 
-        let x_half_extents = GROUND_HALF_EXTENTS_WIDTH * 1.5;
-        let y_half_extents = x_half_extents
-            / (coordinate_system_helper.surface_extents().width as f32
-                / coordinate_system_helper.surface_extents().height as f32);
-
-        coordinate_system_helper
-            .use_visible_range(
-                canvas,
-                skia_safe::Rect {
-                    left: -x_half_extents,
-                    right: x_half_extents,
-                    top: y_half_extents + 1.0,
-                    bottom: -y_half_extents + 1.0,
-                },
-                skia_safe::matrix::ScaleToFit::Center,
-            )
-            .unwrap();
+        if let Some(camera) = &self.camera {
+            coordinate_system_helper.use_camera(canvas, camera);
+        }
 
         // Generally would want to clear data every time we draw
         canvas.clear(skia_safe::Color::from_argb(255, 0, 0, 0));
@@ -324,6 +392,13 @@ impl AppHandler for ExampleApp {
         let mut font = skia_safe::Font::default();
         font.set_size(20.0);
         canvas.draw_str(self.fps_text.clone(), (50, 50), &font, &text_paint);
+        canvas.draw_str("Click to drop a ball", (50, 80), &font, &text_paint);
+        canvas.draw_str(
+            "Scroll to zoom, right-drag to pan, R to reset",
+            (50, 110),
+            &font,
+            &text_paint,
+        );
     }
 
     fn fatal_error(
@@ -0,0 +1,117 @@
+// This example shows the pixel perfect coordinate system. Drawing happens on a small virtual
+// canvas that is scaled up by a whole number without filtering, so the pixels stay sharp at any
+// window size. Try resizing the window to see the letterboxing.
+
+use skulpin::CoordinateSystem;
+use skulpin::LogicalSize;
+use skulpin::PhysicalSize;
+use skulpin::skia_safe;
+
+use skulpin::app::AppBuilder;
+use skulpin::app::AppUpdateArgs;
+use skulpin::app::AppDrawArgs;
+use skulpin::app::AppError;
+use skulpin::app::AppHandler;
+use skulpin::app::VirtualKeyCode;
+
+const VIRTUAL_WIDTH: u32 = 160;
+const VIRTUAL_HEIGHT: u32 = 90;
+
+fn main() {
+    // Setup logging
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Debug)
+        .init();
+
+    let example_app = ExampleApp::new();
+
+    AppBuilder::new()
+        .inner_size(LogicalSize::new(VIRTUAL_WIDTH * 6, VIRTUAL_HEIGHT * 6))
+        .coordinate_system(CoordinateSystem::PixelPerfect {
+            virtual_size: PhysicalSize::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT),
+            letterbox_color: skia_safe::Color::from_argb(255, 20, 20, 20),
+        })
+        .run(example_app);
+}
+
+struct ExampleApp {
+    // Position of the mouse on the virtual canvas, if it can be determined
+    cursor_position: Option<skia_safe::Point>,
+}
+
+impl ExampleApp {
+    pub fn new() -> Self {
+        ExampleApp {
+            cursor_position: None,
+        }
+    }
+}
+
+impl AppHandler for ExampleApp {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+    ) {
+        let input_state = update_args.input_state;
+        let app_control = update_args.app_control;
+
+        if input_state.is_key_down(VirtualKeyCode::Escape) {
+            app_control.enqueue_terminate_process();
+        }
+
+        // The coordinate system helper accounts for the scale and the letterboxing
+        let mouse_position = input_state.mouse_position();
+        self.cursor_position = update_args
+            .coordinate_system_helper
+            .screen_to_canvas((mouse_position.x as f32, mouse_position.y as f32))
+            .ok();
+    }
+
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    ) {
+        let time_state = draw_args.time_state;
+        let canvas = draw_args.canvas;
+
+        canvas.clear(skia_safe::Color::from_argb(255, 92, 148, 252));
+
+        // Anti-aliasing would blend the edges of shapes, so turn it off for crisp pixels
+        let mut paint = skia_safe::Paint::new(skia_safe::Color4f::new(0.0, 0.6, 0.0, 1.0), None);
+        paint.set_anti_alias(false);
+
+        // Ground
+        canvas.draw_rect(
+            skia_safe::Rect::from_xywh(0.0, 70.0, VIRTUAL_WIDTH as f32, 20.0),
+            &paint,
+        );
+
+        // A block that bounces along the ground, snapped to whole pixels
+        let t = time_state.total_time().as_secs_f32();
+        let x = ((t * 40.0) % (VIRTUAL_WIDTH as f32 + 8.0)).floor() - 8.0;
+        let y = 62.0 - ((t * 4.0).sin().abs() * 20.0).floor();
+        paint.set_color(skia_safe::Color::from_argb(255, 228, 92, 16));
+        canvas.draw_rect(skia_safe::Rect::from_xywh(x, y, 8.0, 8.0), &paint);
+
+        // Highlight the virtual pixel under the mouse
+        if let Some(cursor_position) = self.cursor_position {
+            paint.set_color(skia_safe::Color::WHITE);
+            canvas.draw_rect(
+                skia_safe::Rect::from_xywh(
+                    cursor_position.x.floor(),
+                    cursor_position.y.floor(),
+                    1.0,
+                    1.0,
+                ),
+                &paint,
+            );
+        }
+    }
+
+    fn fatal_error(
+        &mut self,
+        error: &AppError,
+    ) {
+        println!("{}", error);
+    }
+}
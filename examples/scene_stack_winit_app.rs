@@ -0,0 +1,222 @@
+// This example shows how to use a SceneStack to switch between a menu, the game, and a pause screen
+// that is drawn over the game
+
+use skulpin::skia_safe;
+use skulpin::LogicalSize;
+
+use skulpin::app::AppBuilder;
+use skulpin::app::AppControl;
+use skulpin::app::AppUpdateArgs;
+use skulpin::app::AppDrawArgs;
+use skulpin::app::AppError;
+use skulpin::app::Scene;
+use skulpin::app::SceneControl;
+use skulpin::app::SceneStack;
+use skulpin::app::SceneTransition;
+use skulpin::app::VirtualKeyCode;
+
+use std::time::Duration;
+
+fn main() {
+    // Setup logging
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Debug)
+        .init();
+
+    AppBuilder::new()
+        .inner_size(LogicalSize::new(900, 600))
+        .run(SceneStack::new(MenuScene));
+}
+
+fn draw_text(
+    canvas: &mut skia_safe::Canvas,
+    text: &str,
+    position: (i32, i32),
+    size: f32,
+) {
+    let mut text_paint = skia_safe::Paint::new(skia_safe::Color4f::new(1.0, 1.0, 0.0, 1.0), None);
+    text_paint.set_anti_alias(true);
+
+    let mut font = skia_safe::Font::default();
+    font.set_size(size);
+    canvas.draw_str(text, position, &font, &text_paint);
+}
+
+//
+// The first scene. Starts the game or quits.
+//
+struct MenuScene;
+
+impl Scene for MenuScene {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+        scene_control: &mut SceneControl,
+    ) {
+        let input_state = update_args.input_state;
+
+        if input_state.is_key_just_down(VirtualKeyCode::Return) {
+            scene_control.replace_scene(
+                GameScene::new(),
+                SceneTransition::Fade {
+                    color: skia_safe::Color::BLACK,
+                    duration: Duration::from_millis(600),
+                },
+            );
+        }
+
+        // Popping the last scene terminates the app
+        if input_state.is_key_just_down(VirtualKeyCode::Escape) {
+            scene_control.pop_scene(SceneTransition::None);
+        }
+    }
+
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    ) {
+        let canvas = draw_args.canvas;
+        canvas.clear(skia_safe::Color::from_argb(255, 30, 30, 60));
+
+        draw_text(canvas, "Menu", (50, 80), 40.0);
+        draw_text(
+            canvas,
+            "Press Enter to start, Escape to quit",
+            (50, 130),
+            20.0,
+        );
+    }
+
+    fn fatal_error(
+        &mut self,
+        error: &AppError,
+    ) {
+        println!("{}", error);
+    }
+}
+
+//
+// A ball bouncing back and forth. It stops moving while paused, since only the top scene updates.
+//
+struct GameScene {
+    position: f32,
+    velocity: f32,
+}
+
+impl GameScene {
+    fn new() -> Self {
+        GameScene {
+            position: 100.0,
+            velocity: 300.0,
+        }
+    }
+}
+
+impl Scene for GameScene {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+        scene_control: &mut SceneControl,
+    ) {
+        let input_state = update_args.input_state;
+        let time_state = update_args.time_state;
+
+        self.position += self.velocity * time_state.previous_update_dt();
+        if (self.position < 50.0 && self.velocity < 0.0)
+            || (self.position > 850.0 && self.velocity > 0.0)
+        {
+            self.velocity = -self.velocity;
+        }
+
+        if input_state.is_key_just_down(VirtualKeyCode::Escape) {
+            scene_control.push_scene(
+                PauseScene,
+                SceneTransition::Crossfade {
+                    duration: Duration::from_millis(200),
+                },
+            );
+        }
+    }
+
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    ) {
+        let canvas = draw_args.canvas;
+        canvas.clear(skia_safe::Color::from_argb(255, 0, 0, 0));
+
+        let mut paint = skia_safe::Paint::new(skia_safe::Color4f::new(0.0, 1.0, 0.0, 1.0), None);
+        paint.set_anti_alias(true);
+        canvas.draw_circle((self.position, 300.0), 40.0, &paint);
+
+        draw_text(canvas, "Press Escape to pause", (50, 50), 20.0);
+    }
+
+    // Don't close the window in the middle of the game, pause instead
+    fn close_requested(
+        &mut self,
+        _app_control: &mut AppControl,
+        scene_control: &mut SceneControl,
+    ) -> bool {
+        scene_control.push_scene(PauseScene, SceneTransition::None);
+        false
+    }
+}
+
+//
+// Drawn over the game, which remains visible but does not update
+//
+struct PauseScene;
+
+impl Scene for PauseScene {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+        scene_control: &mut SceneControl,
+    ) {
+        let input_state = update_args.input_state;
+
+        if input_state.is_key_just_down(VirtualKeyCode::Escape) {
+            scene_control.pop_scene(SceneTransition::Crossfade {
+                duration: Duration::from_millis(200),
+            });
+        }
+
+        // Leave the game and go back to the menu
+        if input_state.is_key_just_down(VirtualKeyCode::Q) {
+            scene_control.pop_scene(SceneTransition::None);
+            scene_control.replace_scene(
+                MenuScene,
+                SceneTransition::Fade {
+                    color: skia_safe::Color::BLACK,
+                    duration: Duration::from_millis(600),
+                },
+            );
+        }
+    }
+
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    ) {
+        let canvas = draw_args.canvas;
+
+        // Darken the game underneath
+        canvas.draw_color(
+            skia_safe::Color::from_argb(160, 0, 0, 0),
+            skia_safe::BlendMode::SrcOver,
+        );
+
+        draw_text(canvas, "Paused", (50, 130), 40.0);
+        draw_text(
+            canvas,
+            "Press Escape to resume, Q to return to the menu",
+            (50, 180),
+            20.0,
+        );
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
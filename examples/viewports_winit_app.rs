@@ -0,0 +1,134 @@
+// This example shows how to draw several coordinate systems into viewports, i.e. for split-screen
+// or a minimap. Each half of the window shows the same scene with a different zoom, and a minimap
+// is drawn in the top-right corner. Letterbox bars are shaded so that they are visible.
+
+use skulpin::CoordinateSystem;
+use skulpin::CoordinateSystemHelper;
+use skulpin::LogicalSize;
+use skulpin::skia_safe;
+
+use skulpin::app::AppBuilder;
+use skulpin::app::AppUpdateArgs;
+use skulpin::app::AppDrawArgs;
+use skulpin::app::AppError;
+use skulpin::app::AppHandler;
+use skulpin::app::VirtualKeyCode;
+
+fn main() {
+    // Setup logging
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Debug)
+        .init();
+
+    let example_app = ExampleApp::new();
+
+    AppBuilder::new()
+        .inner_size(LogicalSize::new(900, 600))
+        .coordinate_system(CoordinateSystem::Physical)
+        .run(example_app);
+}
+
+struct ExampleApp {}
+
+impl ExampleApp {
+    pub fn new() -> Self {
+        ExampleApp {}
+    }
+
+    fn draw_scene(
+        canvas: &mut skia_safe::Canvas,
+        coordinate_system_helper: &CoordinateSystemHelper,
+        t: f32,
+    ) {
+        // Shade the letterbox bars so it's clear which area is the scene
+        if let Ok(letterbox) = coordinate_system_helper.letterbox() {
+            canvas.save();
+            canvas.reset_matrix();
+            let paint = skia_safe::Paint::new(skia_safe::Color4f::new(0.2, 0.2, 0.2, 1.0), None);
+            for bar in &letterbox.bars {
+                canvas.draw_rect(bar, &paint);
+            }
+            canvas.restore();
+        }
+
+        let mut paint = skia_safe::Paint::new(skia_safe::Color4f::new(0.0, 0.0, 0.0, 1.0), None);
+        paint.set_anti_alias(true);
+        canvas.draw_rect(skia_safe::Rect::new(-100.0, -100.0, 100.0, 100.0), &paint);
+
+        paint.set_style(skia_safe::paint::Style::Stroke);
+        paint.set_stroke_width(2.0);
+        paint.set_color(skia_safe::Color::from_argb(255, 100, 200, 255));
+        canvas.draw_rect(skia_safe::Rect::new(-100.0, -100.0, 100.0, 100.0), &paint);
+
+        paint.set_style(skia_safe::paint::Style::Fill);
+        paint.set_color(skia_safe::Color::from_argb(255, 255, 160, 40));
+        canvas.draw_circle((t.cos() * 60.0, t.sin() * 60.0), 15.0, &paint);
+    }
+}
+
+impl AppHandler for ExampleApp {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+    ) {
+        let input_state = update_args.input_state;
+        let app_control = update_args.app_control;
+
+        if input_state.is_key_down(VirtualKeyCode::Escape) {
+            app_control.enqueue_terminate_process();
+        }
+    }
+
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    ) {
+        let t = draw_args.time_state.total_time().as_secs_f32();
+        let canvas = draw_args.canvas;
+        let coordinate_system_helper = draw_args.coordinate_system_helper;
+
+        canvas.clear(skia_safe::Color::from_argb(255, 0, 0, 0));
+
+        // Split the window into left and right halves. The left shows the whole scene and the right
+        // is zoomed in on the moving circle.
+        let halves = coordinate_system_helper.grid_viewport_rects(2, 1);
+        let scale_to_fit = skia_safe::matrix::ScaleToFit::Center;
+        let views = [
+            skia_safe::Rect::new(-110.0, -110.0, 110.0, 110.0),
+            skia_safe::Rect::from_xywh(t.cos() * 60.0 - 40.0, t.sin() * 60.0 - 40.0, 80.0, 80.0),
+        ];
+
+        for (rect, view) in halves.iter().zip(views.iter()) {
+            coordinate_system_helper
+                .draw_viewport(
+                    canvas,
+                    *rect,
+                    CoordinateSystem::VisibleRange(*view, scale_to_fit),
+                    |canvas, viewport_helper| Self::draw_scene(canvas, &viewport_helper, t),
+                )
+                .ok();
+        }
+
+        // Picture-in-picture minimap in the top-right corner
+        let minimap_rect = coordinate_system_helper
+            .fractional_viewport_rect(skia_safe::Rect::new(0.8, 0.0, 1.0, 0.2));
+        coordinate_system_helper
+            .draw_viewport(
+                canvas,
+                minimap_rect,
+                CoordinateSystem::VisibleRange(
+                    skia_safe::Rect::new(-110.0, -110.0, 110.0, 110.0),
+                    scale_to_fit,
+                ),
+                |canvas, viewport_helper| Self::draw_scene(canvas, &viewport_helper, t),
+            )
+            .ok();
+    }
+
+    fn fatal_error(
+        &mut self,
+        error: &AppError,
+    ) {
+        println!("{}", error);
+    }
+}
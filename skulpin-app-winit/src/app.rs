@@ -4,9 +4,25 @@ use crate::skia_safe;
 use crate::winit;
 
 use super::app_control::AppControl;
+use super::app_control::FullscreenMode;
+use super::app_control::Icon;
+use super::app_runner::AppRunner;
+use super::app_event::AppEvent;
+use super::app_event::AppEventSender;
+use super::monitor;
+use super::monitor::MonitorInfo;
+use super::monitor::MonitorSelection;
+use super::task::TaskExecutor;
 use super::input_state::InputState;
+use super::input_state::InputConfig;
 use super::time_state::TimeState;
-use super::util::PeriodicEvent;
+use super::window_state::WindowState;
+use super::window_state::WindowStatePersistence;
+use super::input_state::PhysicalSize;
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
 
 use skulpin_renderer::LogicalSize;
 use skulpin_renderer::Size;
@@ -14,13 +30,15 @@ use skulpin_renderer::RendererBuilder;
 use skulpin_renderer::CoordinateSystem;
 use skulpin_renderer::CoordinateSystemHelper;
 use skulpin_renderer::ValidationMode;
+use skulpin_renderer::PresentMode;
 use skulpin_renderer::rafx::api::RafxError;
-use crate::rafx::api::RafxExtents2D;
+use skulpin_renderer::SkulpinError;
 
 /// Represents an error from creating the renderer
 #[derive(Debug)]
 pub enum AppError {
     RafxError(skulpin_renderer::rafx::api::RafxError),
+    SkulpinError(SkulpinError),
     WinitError(winit::error::OsError),
 }
 
@@ -28,6 +46,7 @@ impl std::error::Error for AppError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
             AppError::RafxError(ref e) => Some(e),
+            AppError::SkulpinError(ref e) => Some(e),
             AppError::WinitError(ref e) => Some(e),
         }
     }
@@ -40,6 +59,7 @@ impl core::fmt::Display for AppError {
     ) -> core::fmt::Result {
         match *self {
             AppError::RafxError(ref e) => e.fmt(fmt),
+            AppError::SkulpinError(ref e) => e.fmt(fmt),
             AppError::WinitError(ref e) => e.fmt(fmt),
         }
     }
@@ -51,16 +71,24 @@ impl From<RafxError> for AppError {
     }
 }
 
+impl From<SkulpinError> for AppError {
+    fn from(result: SkulpinError) -> Self {
+        AppError::SkulpinError(result)
+    }
+}
+
 impl From<winit::error::OsError> for AppError {
     fn from(result: winit::error::OsError) -> Self {
         AppError::WinitError(result)
     }
 }
 
-pub struct AppUpdateArgs<'a, 'b, 'c> {
+pub struct AppUpdateArgs<'a, 'b, 'c, 'd> {
     pub app_control: &'a mut AppControl,
     pub input_state: &'b InputState,
     pub time_state: &'c TimeState,
+    pub task_executor: &'d mut TaskExecutor,
+    pub coordinate_system_helper: CoordinateSystemHelper,
 }
 
 pub struct AppDrawArgs<'a, 'b, 'c, 'd> {
@@ -80,7 +108,13 @@ pub struct AppDrawArgs<'a, 'b, 'c, 'd> {
 ///
 /// I would recommend putting general logic you always want to run in the `update` and just
 /// rendering code in the `draw`.
-pub trait AppHandler {
+///
+/// The remaining callbacks are optional and notify the app of changes to the window. They are
+/// called as the events arrive, before the next `update`.
+///
+/// `U` is the type of event that can be sent to the app with an `AppEventSender`. See
+/// `AppBuilder::event_sender`
+pub trait AppHandler<U = ()> {
     /// Called frequently, this is the intended place to put non-rendering logic
     fn update(
         &mut self,
@@ -97,12 +131,135 @@ pub trait AppHandler {
         &mut self,
         error: &AppError,
     );
+
+    /// Called once when the app is terminating, including after a fatal error. This is the place
+    /// to flush any state that needs to be saved. The app handler is dropped immediately after,
+    /// before the renderer is destroyed.
+    fn shutdown(&mut self) {}
+
+    /// Called after the renderer was recreated because the GPU device or the window's surface was
+    /// lost (i.e. the driver was reset). Skia images and surfaces that were created on the GPU
+    /// can't be used anymore and must be created again.
+    fn renderer_recreated(
+        &mut self,
+        _app_control: &mut AppControl,
+    ) {
+    }
+
+    /// Called when the user tries to close the window. Return false to keep the window open (for
+    /// example, to ask about unsaved changes first). The app can still be closed later with
+    /// `AppControl::enqueue_terminate_process`
+    fn close_requested(
+        &mut self,
+        _app_control: &mut AppControl,
+    ) -> bool {
+        true
+    }
+
+    /// Called when the window is resized. `inner_size` is in physical pixels
+    fn resized(
+        &mut self,
+        _app_control: &mut AppControl,
+        _inner_size: PhysicalSize<u32>,
+    ) {
+    }
+
+    /// Called when the window's scale factor changes, for example when it is moved to a monitor
+    /// with a different DPI
+    fn scale_factor_changed(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scale_factor: f64,
+    ) {
+    }
+
+    /// Called when the window moves to a different monitor, or the properties of its monitor
+    /// change. If the new monitor has a different DPI, `scale_factor_changed` is called as well.
+    fn monitor_changed(
+        &mut self,
+        _app_control: &mut AppControl,
+        _monitor: &MonitorInfo,
+    ) {
+    }
+
+    /// Called when the window gains keyboard focus
+    fn focus_gained(
+        &mut self,
+        _app_control: &mut AppControl,
+    ) {
+    }
+
+    /// Called when the window loses keyboard focus
+    fn focus_lost(
+        &mut self,
+        _app_control: &mut AppControl,
+    ) {
+    }
+
+    /// Called when the window is minimized. winit reports this as a resize to zero, which is
+    /// reported here instead of `resized`
+    fn minimized(
+        &mut self,
+        _app_control: &mut AppControl,
+    ) {
+    }
+
+    /// Called when the window is restored after being minimized
+    fn restored(
+        &mut self,
+        _app_control: &mut AppControl,
+    ) {
+    }
+
+    /// Called when a file is dragged over the window. This is called once per file
+    fn file_hovered(
+        &mut self,
+        _app_control: &mut AppControl,
+        _path: &Path,
+    ) {
+    }
+
+    /// Called when files that were dragged over the window leave it without being dropped
+    fn file_hover_cancelled(
+        &mut self,
+        _app_control: &mut AppControl,
+    ) {
+    }
+
+    /// Called when a file is dropped on the window. This is called once per file
+    fn file_dropped(
+        &mut self,
+        _app_control: &mut AppControl,
+        _path: &Path,
+    ) {
+    }
+
+    /// Called when an event is sent to the app with an `AppEventSender`
+    fn user_event(
+        &mut self,
+        _app_control: &mut AppControl,
+        _event: U,
+    ) {
+    }
 }
 
-/// Used to configure the app behavior and create the app
-pub struct AppBuilder {
+/// Used to configure the app behavior and create the app. `U` is the type of event that can be
+/// sent to the app, see `AppBuilder::event_sender`
+pub struct AppBuilder<U: 'static = ()> {
+    event_loop: Option<winit::event_loop::EventLoop<AppEvent<U>>>,
     inner_size: Size,
+    min_inner_size: Option<Size>,
+    max_inner_size: Option<Size>,
+    position: Option<winit::dpi::Position>,
     window_title: String,
+    window_icon: Option<Icon>,
+    resizable: bool,
+    decorations: bool,
+    maximized: bool,
+    fullscreen_mode: FullscreenMode,
+    window_state_path: Option<PathBuf>,
+    monitor: Option<MonitorSelection>,
+    input_config: InputConfig,
     renderer_builder: RendererBuilder,
 }
 
@@ -112,12 +269,32 @@ impl Default for AppBuilder {
     }
 }
 
-impl AppBuilder {
+impl AppBuilder<()> {
     /// Construct the app builder initialized with default options
     pub fn new() -> Self {
+        Self::with_user_event()
+    }
+}
+
+impl<U: 'static> AppBuilder<U> {
+    /// Construct the app builder initialized with default options, for an app that can receive
+    /// events of type `U`. Use `event_sender` to send them.
+    pub fn with_user_event() -> Self {
         AppBuilder {
+            event_loop: None,
             inner_size: LogicalSize::new(900, 600).into(),
+            min_inner_size: None,
+            max_inner_size: None,
+            position: None,
             window_title: "Skulpin".to_string(),
+            window_icon: None,
+            resizable: true,
+            decorations: true,
+            maximized: false,
+            fullscreen_mode: FullscreenMode::Windowed,
+            window_state_path: None,
+            monitor: None,
+            input_config: InputConfig::default(),
             renderer_builder: RendererBuilder::new(),
         }
     }
@@ -131,6 +308,36 @@ impl AppBuilder {
         self
     }
 
+    /// Specifies the minimum inner size of the window. Both physical and logical coordinates are
+    /// accepted.
+    pub fn min_inner_size<S: Into<Size>>(
+        mut self,
+        min_inner_size: S,
+    ) -> Self {
+        self.min_inner_size = Some(min_inner_size.into());
+        self
+    }
+
+    /// Specifies the maximum inner size of the window. Both physical and logical coordinates are
+    /// accepted.
+    pub fn max_inner_size<S: Into<Size>>(
+        mut self,
+        max_inner_size: S,
+    ) -> Self {
+        self.max_inner_size = Some(max_inner_size.into());
+        self
+    }
+
+    /// Specifies the position of the top-left of the window, including the titlebar. If not set,
+    /// the OS will choose.
+    pub fn position<P: Into<winit::dpi::Position>>(
+        mut self,
+        position: P,
+    ) -> Self {
+        self.position = Some(position.into());
+        self
+    }
+
     /// Specifies the title that the window will be created with
     pub fn window_title<T: Into<String>>(
         mut self,
@@ -140,6 +347,84 @@ impl AppBuilder {
         self
     }
 
+    /// Specifies the icon used for the window titlebar, taskbar, etc.
+    pub fn window_icon(
+        mut self,
+        window_icon: Icon,
+    ) -> Self {
+        self.window_icon = Some(window_icon);
+        self
+    }
+
+    /// Specifies whether the user can resize the window. Defaults to true.
+    pub fn resizable(
+        mut self,
+        resizable: bool,
+    ) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Specifies whether the window has a titlebar and borders. Defaults to true.
+    pub fn decorations(
+        mut self,
+        decorations: bool,
+    ) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Specifies whether the window starts maximized. Defaults to false.
+    pub fn maximized(
+        mut self,
+        maximized: bool,
+    ) -> Self {
+        self.maximized = maximized;
+        self
+    }
+
+    /// Specifies whether the window starts fullscreen. Defaults to `FullscreenMode::Windowed`.
+    pub fn fullscreen_mode(
+        mut self,
+        fullscreen_mode: FullscreenMode,
+    ) -> Self {
+        self.fullscreen_mode = fullscreen_mode;
+        self
+    }
+
+    /// Specifies the monitor the window opens on. The window is centered on the monitor. This is
+    /// ignored if a position is set, or the window state was restored by `persist_window_state`.
+    pub fn monitor(
+        mut self,
+        monitor: MonitorSelection,
+    ) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Save the window's position, size, maximized state and monitor to the given file when the app
+    /// exits, and restore them the next time the app runs. The saved state overrides `inner_size`,
+    /// `position` and `maximized`. If the window would no longer be visible (i.e. a monitor was
+    /// disconnected), the saved position is ignored.
+    ///
+    /// The maximized state is only saved with winit 0.25 and later.
+    pub fn persist_window_state<P: Into<PathBuf>>(
+        mut self,
+        path: P,
+    ) -> Self {
+        self.window_state_path = Some(path.into());
+        self
+    }
+
+    /// Specifies the thresholds used for detecting drags, multi-clicks, and long presses
+    pub fn input_config(
+        mut self,
+        input_config: InputConfig,
+    ) -> Self {
+        self.input_config = input_config;
+        self
+    }
+
     /// Determine the coordinate system to use for the canvas. This can be overridden by using the
     /// canvas sizer passed into the draw callback
     pub fn coordinate_system(
@@ -160,154 +445,394 @@ impl AppBuilder {
         self
     }
 
+    /// Enabling vsync uses `PresentMode::VSYNC_ON`, disabling it tries the modes in
+    /// `PresentMode::VSYNC_OFF` in order. Defaults to true. This replaces the modes set with
+    /// `present_modes`.
+    pub fn vsync_enabled(
+        mut self,
+        vsync_enabled: bool,
+    ) -> Self {
+        self.renderer_builder = self.renderer_builder.vsync_enabled(vsync_enabled);
+        self
+    }
+
+    /// The present modes to try, in order of preference. See `RendererBuilder::present_modes`.
+    pub fn present_modes(
+        mut self,
+        present_modes: &[PresentMode],
+    ) -> Self {
+        self.renderer_builder = self.renderer_builder.present_modes(present_modes);
+        self
+    }
+
+    /// Limit how many frames the CPU can queue ahead of the GPU. Use 1 for the lowest latency.
+    pub fn max_frames_in_flight(
+        mut self,
+        max_frames_in_flight: usize,
+    ) -> Self {
+        self.renderer_builder = self
+            .renderer_builder
+            .max_frames_in_flight(max_frames_in_flight);
+        self
+    }
+
+    /// Returns a sender that can be used to send events to the app once it is running, including
+    /// from other threads. Events are passed to `AppHandler::user_event`.
+    ///
+    /// This creates the event loop, so it must be called on the main thread.
+    pub fn event_sender(&mut self) -> AppEventSender<U> {
+        let event_loop = self
+            .event_loop
+            .get_or_insert_with(winit::event_loop::EventLoop::with_user_event);
+
+        AppEventSender::new(event_loop.create_proxy())
+    }
+
     /// Start the app. `app_handler` must be an implementation of [skulpin::app::AppHandler].
     /// This does not return because winit does not return. For consistency, we use the
     /// fatal_error() callback on the passed in AppHandler.
-    pub fn run<T: 'static + AppHandler>(
+    pub fn run<T: 'static + AppHandler<U>>(
         self,
         app_handler: T,
-    ) -> ! {
-        App::run(
-            app_handler,
-            self.inner_size,
-            self.window_title.clone(),
-            self.renderer_builder,
-        )
+    ) -> !
+    where
+        U: Send,
+    {
+        App::run(app_handler, self)
+    }
+
+    /// Start the app, returning once it terminates. The error that caused the app to terminate, if
+    /// any, is returned after being passed to the fatal_error() callback. This is only available
+    /// on desktop platforms.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub fn run_return<T: AppHandler<U>>(
+        self,
+        app_handler: T,
+    ) -> Result<(), AppError>
+    where
+        U: Send,
+    {
+        App::run_return(app_handler, self)
+    }
+}
+
+fn winit_size(size: Size) -> winit::dpi::Size {
+    match size {
+        Size::Physical(physical_size) => winit::dpi::Size::Physical(winit::dpi::PhysicalSize::new(
+            physical_size.width,
+            physical_size.height,
+        )),
+        Size::Logical(logical_size) => winit::dpi::Size::Logical(winit::dpi::LogicalSize::new(
+            logical_size.width as f64,
+            logical_size.height as f64,
+        )),
     }
 }
 
+// Returns the position that centers a window of the given size on the monitor
+fn centered_position(
+    inner_size: Size,
+    monitor: &winit::monitor::MonitorHandle,
+) -> winit::dpi::PhysicalPosition<i32> {
+    let inner_size = match inner_size {
+        Size::Physical(physical_size) => physical_size,
+        Size::Logical(logical_size) => logical_size.to_physical(monitor.scale_factor()),
+    };
+
+    let monitor_position = monitor.position();
+    let monitor_size = monitor.size();
+    let offset_x = monitor_size.width.saturating_sub(inner_size.width) / 2;
+    let offset_y = monitor_size.height.saturating_sub(inner_size.height) / 2;
+
+    winit::dpi::PhysicalPosition::new(
+        monitor_position.x + offset_x as i32,
+        monitor_position.y + offset_y as i32,
+    )
+}
+
 /// Constructed by `AppBuilder` which immediately calls `run`.
 pub struct App {}
 
 impl App {
     /// Runs the app. This is called by `AppBuilder::run`. This does not return because winit does
     /// not return. For consistency, we use the fatal_error() callback on the passed in AppHandler.
-    pub fn run<T: 'static + AppHandler>(
-        mut app_handler: T,
-        inner_size: Size,
-        window_title: String,
-        renderer_builder: RendererBuilder,
+    ///
+    /// The process exits with a non-zero exit code if the app terminated because of an error.
+    pub fn run<T: 'static + AppHandler<U>, U: 'static + Send>(
+        app_handler: T,
+        mut app_builder: AppBuilder<U>,
     ) -> ! {
-        // Create the event loop
-        let event_loop = winit::event_loop::EventLoop::<()>::with_user_event();
-
-        let winit_size = match inner_size {
-            Size::Physical(physical_size) => winit::dpi::Size::Physical(
-                winit::dpi::PhysicalSize::new(physical_size.width, physical_size.height),
-            ),
-            Size::Logical(logical_size) => winit::dpi::Size::Logical(winit::dpi::LogicalSize::new(
-                logical_size.width as f64,
-                logical_size.height as f64,
-            )),
-        };
+        // Create the event loop, unless it was already created to provide an event sender
+        let event_loop = app_builder
+            .event_loop
+            .take()
+            .unwrap_or_else(winit::event_loop::EventLoop::with_user_event);
+
+        let event_loop_proxy = event_loop.create_proxy();
+        let (window, mut app_runner, mut window_state_persistence) =
+            match Self::create(app_handler, app_builder, &event_loop, event_loop_proxy) {
+                Ok(result) => result,
+                Err(_) => std::process::exit(1),
+            };
 
-        // Create a single window
-        let window_result = winit::window::WindowBuilder::new()
-            .with_title(window_title)
-            .with_inner_size(winit_size)
-            .build(&event_loop);
+        // Pass control of this thread to winit until the app terminates. If this app wants to quit,
+        // the update loop should send the appropriate event via the channel
+        event_loop.run(move |event, window_target, control_flow| {
+            let is_loop_destroyed = matches!(event, winit::event::Event::LoopDestroyed);
+
+            Self::handle_event(
+                &window,
+                &mut app_runner,
+                &mut window_state_persistence,
+                event,
+                window_target,
+                control_flow,
+            );
+
+            // winit exits the process with a success code when the loop ends, so exit early if the
+            // app is terminating because of an error
+            if is_loop_destroyed && app_runner.fatal_error().is_some() {
+                std::process::exit(1);
+            }
+        });
+    }
 
-        let window = match window_result {
-            Ok(window) => window,
-            Err(e) => {
-                warn!("Passing WindowBuilder::build() error to app {}", e);
+    /// Runs the app, returning when it terminates. This is called by `AppBuilder::run_return`.
+    ///
+    /// winit only supports returning from the event loop on desktop platforms, and recommends
+    /// against creating a second event loop after the first has returned.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub fn run_return<T: AppHandler<U>, U: 'static + Send>(
+        app_handler: T,
+        mut app_builder: AppBuilder<U>,
+    ) -> Result<(), AppError> {
+        #[cfg(any(feature = "winit-21", feature = "winit-22", feature = "winit-23"))]
+        use winit::platform::desktop::EventLoopExtDesktop;
+
+        #[cfg(any(feature = "winit-24", feature = "winit-25", feature = "winit-latest"))]
+        use winit::platform::run_return::EventLoopExtRunReturn;
+
+        // Create the event loop, unless it was already created to provide an event sender
+        let mut event_loop = app_builder
+            .event_loop
+            .take()
+            .unwrap_or_else(winit::event_loop::EventLoop::with_user_event);
+
+        let event_loop_proxy = event_loop.create_proxy();
+        let (window, mut app_runner, mut window_state_persistence) =
+            Self::create(app_handler, app_builder, &event_loop, event_loop_proxy)?;
+
+        event_loop.run_return(|event, window_target, control_flow| {
+            Self::handle_event(
+                &window,
+                &mut app_runner,
+                &mut window_state_persistence,
+                event,
+                window_target,
+                control_flow,
+            );
+        });
 
-                let app_error = e.into();
-                app_handler.fatal_error(&app_error);
+        match app_runner.take_fatal_error() {
+            Some(app_error) => Err(app_error),
+            None => Ok(()),
+        }
+    }
 
-                // Exiting in this way is consistent with how we will exit if we fail within the
-                // input loop
-                std::process::exit(0);
+    /// Creates the window and the runner that will drive the app. If this fails, the app handler
+    /// is notified of the error and shut down.
+    ///
+    /// This takes the `EventLoop` rather than an `EventLoopWindowTarget` because winit 0.21 and
+    /// 0.22 only list monitors from the `EventLoop`.
+    #[allow(clippy::type_complexity)]
+    fn create<T: AppHandler<U>, U: 'static + Send>(
+        mut app_handler: T,
+        mut app_builder: AppBuilder<U>,
+        event_loop: &winit::event_loop::EventLoop<AppEvent<U>>,
+        event_loop_proxy: winit::event_loop::EventLoopProxy<AppEvent<U>>,
+    ) -> Result<
+        (
+            winit::window::Window,
+            AppRunner<T, U>,
+            Option<WindowStatePersistence>,
+        ),
+        AppError,
+    > {
+        let (monitors, primary_monitor) = monitor::event_loop_monitors(event_loop);
+
+        // Restore the window from the previous run, if it was saved
+        if let Some(window_state_path) = &app_builder.window_state_path {
+            if let Some(window_state) = WindowState::load(window_state_path) {
+                let window_state = window_state.validate(monitors.iter().cloned());
+                debug!("Restoring window state {:?}", window_state);
+
+                app_builder.inner_size = Size::Physical(skulpin_renderer::PhysicalSize::new(
+                    window_state.inner_size.width,
+                    window_state.inner_size.height,
+                ));
+                app_builder.maximized = window_state.maximized;
+                if let Some(position) = window_state.position {
+                    app_builder.position = Some(position.into());
+                }
             }
-        };
+        }
 
-        let mut app_control = AppControl::default();
-        let mut time_state = TimeState::new();
-        let mut input_state = InputState::new(&window);
+        // Center the window on the requested monitor
+        if app_builder.position.is_none() {
+            if let Some(monitor_selection) = &app_builder.monitor {
+                match monitor_selection.find(&monitors, primary_monitor.as_ref()) {
+                    Some(monitor) => {
+                        app_builder.position =
+                            Some(centered_position(app_builder.inner_size, &monitor).into());
+                    }
+                    None => warn!("Monitor {:?} was not found", monitor_selection),
+                }
+            }
+        }
 
-        let window_size = window.inner_size();
-        let window_extents = RafxExtents2D {
-            width: window_size.width,
-            height: window_size.height,
-        };
+        // Create a single window
+        let mut window_builder = winit::window::WindowBuilder::new()
+            .with_title(app_builder.window_title)
+            .with_inner_size(winit_size(app_builder.inner_size))
+            .with_window_icon(app_builder.window_icon)
+            .with_resizable(app_builder.resizable)
+            .with_decorations(app_builder.decorations)
+            .with_maximized(app_builder.maximized);
+
+        if let Some(min_inner_size) = app_builder.min_inner_size {
+            window_builder = window_builder.with_min_inner_size(winit_size(min_inner_size));
+        }
+
+        if let Some(max_inner_size) = app_builder.max_inner_size {
+            window_builder = window_builder.with_max_inner_size(winit_size(max_inner_size));
+        }
+
+        // Older versions of winit can't set the position until the window is created (see below)
+        #[cfg(any(feature = "winit-25", feature = "winit-latest"))]
+        {
+            if let Some(position) = app_builder.position {
+                window_builder = window_builder.with_position(position);
+            }
+        }
 
-        let renderer_result = renderer_builder.build(&window, window_extents);
-        let mut renderer = match renderer_result {
-            Ok(renderer) => renderer,
+        let window = match window_builder.build(event_loop) {
+            Ok(window) => window,
             Err(e) => {
-                warn!("Passing RendererBuilder::build() error to app {}", e);
+                warn!("Passing WindowBuilder::build() error to app {}", e);
 
                 let app_error = e.into();
                 app_handler.fatal_error(&app_error);
-
-                // Exiting in this way is consistent with how we will exit if we fail within the
-                // input loop
-                std::process::exit(0);
+                app_handler.shutdown();
+                return Err(app_error);
             }
         };
 
-        // To print fps once per second
-        let mut print_fps_event = PeriodicEvent::default();
-
-        // Pass control of this thread to winit until the app terminates. If this app wants to quit,
-        // the update loop should send the appropriate event via the channel
-        event_loop.run(move |event, window_target, control_flow| {
-            input_state.handle_winit_event(&mut app_control, &event, window_target);
-
-            match event {
-                winit::event::Event::MainEventsCleared => {
-                    time_state.update();
+        let window_state_persistence = app_builder
+            .window_state_path
+            .take()
+            .map(|path| WindowStatePersistence::new(path, &window));
 
-                    if print_fps_event.try_take_event(
-                        time_state.current_instant(),
-                        std::time::Duration::from_secs(1),
-                    ) {
-                        debug!("fps: {}", time_state.updates_per_second());
-                    }
+        let mut app_runner = AppRunner::new(
+            app_handler,
+            &window,
+            app_builder.input_config,
+            app_builder.renderer_builder,
+        )?;
+
+        // Older versions of winit can't set the position with WindowBuilder
+        #[cfg(not(any(feature = "winit-25", feature = "winit-latest")))]
+        {
+            if let Some(position) = app_builder.position {
+                app_runner.app_control_mut().set_outer_position(position);
+            }
+        }
 
-                    app_handler.update(AppUpdateArgs {
-                        app_control: &mut app_control,
-                        input_state: &input_state,
-                        time_state: &time_state,
-                    });
+        // Fullscreen is applied after the window is created so that it can use whichever monitor
+        // the window was placed on
+        if app_builder.fullscreen_mode != FullscreenMode::Windowed {
+            app_runner
+                .app_control_mut()
+                .set_fullscreen(app_builder.fullscreen_mode);
+        }
 
-                    // Call this to mark the start of the next frame (i.e. "key just down" will return false)
-                    input_state.end_frame();
+        app_runner.app_control_mut().apply_window_commands(&window);
+
+        // When a task is woken, send an event to wake the event loop. The task will be polled
+        // during the next update
+        let event_loop_proxy = Mutex::new(event_loop_proxy);
+        app_runner
+            .task_executor_mut()
+            .set_event_loop_waker(move || {
+                let _ = event_loop_proxy
+                    .lock()
+                    .unwrap()
+                    .send_event(AppEvent::WakeTasks);
+            });
+
+        Ok((window, app_runner, window_state_persistence))
+    }
 
-                    // Queue a RedrawRequested event.
-                    window.request_redraw();
-                }
-                winit::event::Event::RedrawRequested(_window_id) => {
-                    let window_size = window.inner_size();
-                    let window_extents = RafxExtents2D {
-                        width: window_size.width,
-                        height: window_size.height,
-                    };
-
-                    if let Err(e) = renderer.draw(
-                        window_extents,
-                        window.scale_factor(),
-                        |canvas, coordinate_system_helper| {
-                            app_handler.draw(AppDrawArgs {
-                                app_control: &app_control,
-                                input_state: &input_state,
-                                time_state: &time_state,
-                                canvas,
-                                coordinate_system_helper,
-                            });
-                        },
-                    ) {
-                        warn!("Passing Renderer::draw() error to app {}", e);
-                        app_handler.fatal_error(&e.into());
-                        app_control.enqueue_terminate_process();
-                    }
+    fn handle_event<T: AppHandler<U>, U: 'static>(
+        window: &winit::window::Window,
+        app_runner: &mut AppRunner<T, U>,
+        window_state_persistence: &mut Option<WindowStatePersistence>,
+        event: winit::event::Event<AppEvent<U>>,
+        window_target: &winit::event_loop::EventLoopWindowTarget<AppEvent<U>>,
+        control_flow: &mut winit::event_loop::ControlFlow,
+    ) {
+        app_runner.handle_event(window, &event, window_target);
+
+        if let Some(window_state_persistence) = window_state_persistence {
+            match &event {
+                winit::event::Event::WindowEvent {
+                    window_id,
+                    event: winit::event::WindowEvent::Resized(_),
                 }
+                | winit::event::Event::WindowEvent {
+                    window_id,
+                    event: winit::event::WindowEvent::Moved(_),
+                } if *window_id == window.id() => window_state_persistence.update(window),
+                winit::event::Event::LoopDestroyed => window_state_persistence.save(),
                 _ => {}
             }
+        }
 
-            if app_control.should_terminate_process() {
-                *control_flow = winit::event_loop::ControlFlow::Exit
+        match event {
+            winit::event::Event::UserEvent(AppEvent::User(user_event)) => {
+                app_runner.user_event(user_event)
             }
-        });
+            // The event only needs to wake the event loop. Tasks are polled during the update
+            winit::event::Event::UserEvent(AppEvent::WakeTasks) => {}
+            winit::event::Event::MainEventsCleared => {
+                app_runner.update(window);
+
+                // Queue a RedrawRequested event.
+                window.request_redraw();
+            }
+            winit::event::Event::RedrawRequested(window_id) if window_id == window.id() => {
+                app_runner.draw(window);
+            }
+            _ => {}
+        }
+
+        if app_runner.should_terminate() {
+            *control_flow = winit::event_loop::ControlFlow::Exit
+        }
     }
 }
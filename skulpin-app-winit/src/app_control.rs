@@ -1,11 +1,121 @@
 //! Serves as the interface for an app implementation to affect the behavior of the app that's
 //! hosting it
 
+use crate::winit;
+
+// Re-export winit types
+pub use winit::window::CursorIcon;
+pub use winit::window::Icon;
+
+use crate::winit::dpi::Position;
+use crate::winit::dpi::Size;
+use crate::winit::window::Window;
+use crate::winit::window::Fullscreen;
+
+use super::monitor;
+use super::monitor::VideoModeInfo;
+use super::clipboard::Clipboard;
+use super::clipboard::ClipboardBackend;
+
+/// Determines whether the window covers the whole monitor
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FullscreenMode {
+    /// A normal window
+    Windowed,
+
+    /// A borderless window covering the monitor the window is currently on
+    Borderless,
+
+    /// Exclusive fullscreen on the monitor the window is currently on, which changes the monitor's
+    /// video mode. Pick a mode from `MonitorInfo::video_modes`. If it's `None` or the monitor
+    /// doesn't support it, the largest mode with the highest refresh rate is used.
+    Exclusive(Option<VideoModeInfo>),
+}
+
+impl Default for FullscreenMode {
+    fn default() -> Self {
+        FullscreenMode::Windowed
+    }
+}
+
+/// Determines whether the cursor is restricted to the window
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CursorGrabMode {
+    /// The cursor can move freely
+    None,
+
+    /// The cursor cannot leave the window
+    Confined,
+
+    /// The cursor is grabbed and hidden. Use `InputState::mouse_motion` to read relative motion,
+    /// as the cursor position will stop changing once it reaches the edge of the window.
+    ///
+    /// winit does not distinguish between confining and locking the cursor. On macOS and Wayland,
+    /// grabbing the cursor always locks it in place.
+    Locked,
+}
+
+/// A change to the window requested by the app. These are queued on `AppControl` and applied
+/// after `update` returns
+#[derive(Clone, Debug)]
+pub enum WindowCommand {
+    /// Change the title of the window
+    SetTitle(String),
+
+    /// Change the icon used for the window titlebar, taskbar, etc.
+    SetWindowIcon(Option<Icon>),
+
+    /// Switch between windowed and fullscreen
+    SetFullscreen(FullscreenMode),
+
+    /// Resize the window. The size does not include the titlebar and borders
+    SetInnerSize(Size),
+
+    /// Prevent the window from being resized smaller than the given size
+    SetMinInnerSize(Option<Size>),
+
+    /// Prevent the window from being resized larger than the given size
+    SetMaxInnerSize(Option<Size>),
+
+    /// Move the window. The position is the top-left of the window including the titlebar
+    SetOuterPosition(Position),
+
+    /// Maximize or restore the window
+    SetMaximized(bool),
+
+    /// Minimize or restore the window
+    SetMinimized(bool),
+
+    /// Show or hide the titlebar and borders
+    SetDecorations(bool),
+
+    /// Allow or prevent the user from resizing the window
+    SetResizable(bool),
+
+    /// Change the icon of the cursor while it is over the window
+    SetCursorIcon(CursorIcon),
+
+    /// Show or hide the cursor while it is over the window
+    SetCursorVisible(bool),
+
+    /// Confine or lock the cursor to the window
+    SetCursorGrab(CursorGrabMode),
+
+    /// Move the cursor to the given position, relative to the top-left of the window
+    SetCursorPosition(Position),
+}
+
 /// State that drives high-level decision making for the app
 #[derive(Default)]
 pub struct AppControl {
     /// If true, the application will quit when the next frame ends
     should_terminate_process: bool,
+
+    /// Changes to the window that will be applied after the current update
+    window_commands: Vec<WindowCommand>,
+
+    /// Reads and writes the clipboard
+    clipboard: Clipboard,
 }
 
 impl AppControl {
@@ -18,4 +128,227 @@ impl AppControl {
     pub fn should_terminate_process(&self) -> bool {
         self.should_terminate_process
     }
+
+    /// Queue a change to the window. Commands are applied in order after `update` returns
+    pub fn enqueue_window_command(
+        &mut self,
+        window_command: WindowCommand,
+    ) {
+        self.window_commands.push(window_command);
+    }
+
+    /// Returns the window commands that have been queued but not yet applied
+    pub fn pending_window_commands(&self) -> &[WindowCommand] {
+        &self.window_commands
+    }
+
+    /// Change the title of the window
+    pub fn set_title<T: Into<String>>(
+        &mut self,
+        title: T,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetTitle(title.into()));
+    }
+
+    /// Change the icon used for the window titlebar, taskbar, etc.
+    pub fn set_window_icon(
+        &mut self,
+        window_icon: Option<Icon>,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetWindowIcon(window_icon));
+    }
+
+    /// Switch between windowed and fullscreen
+    pub fn set_fullscreen(
+        &mut self,
+        fullscreen_mode: FullscreenMode,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetFullscreen(fullscreen_mode));
+    }
+
+    /// Resize the window. Both physical and logical sizes are accepted.
+    pub fn set_inner_size<S: Into<Size>>(
+        &mut self,
+        inner_size: S,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetInnerSize(inner_size.into()));
+    }
+
+    /// Prevent the window from being resized smaller than the given size. Pass None to remove the
+    /// limit.
+    pub fn set_min_inner_size<S: Into<Size>>(
+        &mut self,
+        min_inner_size: Option<S>,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetMinInnerSize(
+            min_inner_size.map(|size| size.into()),
+        ));
+    }
+
+    /// Prevent the window from being resized larger than the given size. Pass None to remove the
+    /// limit.
+    pub fn set_max_inner_size<S: Into<Size>>(
+        &mut self,
+        max_inner_size: Option<S>,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetMaxInnerSize(
+            max_inner_size.map(|size| size.into()),
+        ));
+    }
+
+    /// Move the window. Both physical and logical positions are accepted.
+    pub fn set_outer_position<P: Into<Position>>(
+        &mut self,
+        position: P,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetOuterPosition(position.into()));
+    }
+
+    /// Maximize or restore the window
+    pub fn set_maximized(
+        &mut self,
+        maximized: bool,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetMaximized(maximized));
+    }
+
+    /// Minimize or restore the window
+    pub fn set_minimized(
+        &mut self,
+        minimized: bool,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetMinimized(minimized));
+    }
+
+    /// Show or hide the titlebar and borders
+    pub fn set_decorations(
+        &mut self,
+        decorations: bool,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetDecorations(decorations));
+    }
+
+    /// Allow or prevent the user from resizing the window
+    pub fn set_resizable(
+        &mut self,
+        resizable: bool,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetResizable(resizable));
+    }
+
+    /// Change the icon of the cursor while it is over the window
+    pub fn set_cursor_icon(
+        &mut self,
+        cursor_icon: CursorIcon,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetCursorIcon(cursor_icon));
+    }
+
+    /// Show or hide the cursor while it is over the window
+    pub fn set_cursor_visible(
+        &mut self,
+        visible: bool,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetCursorVisible(visible));
+    }
+
+    /// Confine or lock the cursor to the window. Any mode other than `CursorGrabMode::Locked` will
+    /// show the cursor again.
+    pub fn set_cursor_grab(
+        &mut self,
+        cursor_grab_mode: CursorGrabMode,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetCursorGrab(cursor_grab_mode));
+    }
+
+    /// Move the cursor to the given position, relative to the top-left of the window. Both
+    /// physical and logical positions are accepted.
+    pub fn set_cursor_position<P: Into<Position>>(
+        &mut self,
+        position: P,
+    ) {
+        self.enqueue_window_command(WindowCommand::SetCursorPosition(position.into()));
+    }
+
+    /// Access the clipboard. This is the system clipboard if the `clipboard` feature is enabled,
+    /// otherwise an in-memory clipboard that is private to the app.
+    pub fn clipboard(&mut self) -> &mut Clipboard {
+        &mut self.clipboard
+    }
+
+    /// Replace the clipboard backend, i.e. with a `MemoryClipboard` in tests
+    pub fn set_clipboard_backend<B: ClipboardBackend + 'static>(
+        &mut self,
+        backend: B,
+    ) {
+        self.clipboard = Clipboard::with_backend(backend);
+    }
+
+    /// Apply all queued window commands to the given window
+    pub(crate) fn apply_window_commands(
+        &mut self,
+        window: &Window,
+    ) {
+        for window_command in self.window_commands.drain(..) {
+            trace!("applying window command {:?}", window_command);
+            match window_command {
+                WindowCommand::SetTitle(title) => window.set_title(&title),
+                WindowCommand::SetWindowIcon(window_icon) => window.set_window_icon(window_icon),
+                WindowCommand::SetFullscreen(fullscreen_mode) => {
+                    let fullscreen = match fullscreen_mode {
+                        FullscreenMode::Windowed => None,
+                        FullscreenMode::Borderless => {
+                            // current_monitor() returns an Option starting with winit 0.23, and so
+                            // does Fullscreen::Borderless
+                            Some(Fullscreen::Borderless(window.current_monitor()))
+                        }
+                        FullscreenMode::Exclusive(video_mode) => {
+                            let video_mode = monitor::current_monitor(window)
+                                .and_then(|monitor| monitor::find_video_mode(&monitor, video_mode));
+
+                            match video_mode {
+                                Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                                None => {
+                                    warn!(
+                                        "No video mode is available for exclusive fullscreen, \
+                                         using borderless fullscreen instead"
+                                    );
+                                    Some(Fullscreen::Borderless(window.current_monitor()))
+                                }
+                            }
+                        }
+                    };
+
+                    window.set_fullscreen(fullscreen);
+                }
+                WindowCommand::SetInnerSize(inner_size) => window.set_inner_size(inner_size),
+                WindowCommand::SetMinInnerSize(min_inner_size) => {
+                    window.set_min_inner_size(min_inner_size)
+                }
+                WindowCommand::SetMaxInnerSize(max_inner_size) => {
+                    window.set_max_inner_size(max_inner_size)
+                }
+                WindowCommand::SetOuterPosition(position) => window.set_outer_position(position),
+                WindowCommand::SetMaximized(maximized) => window.set_maximized(maximized),
+                WindowCommand::SetMinimized(minimized) => window.set_minimized(minimized),
+                WindowCommand::SetDecorations(decorations) => window.set_decorations(decorations),
+                WindowCommand::SetResizable(resizable) => window.set_resizable(resizable),
+                WindowCommand::SetCursorIcon(cursor_icon) => window.set_cursor_icon(cursor_icon),
+                WindowCommand::SetCursorVisible(visible) => window.set_cursor_visible(visible),
+                WindowCommand::SetCursorGrab(cursor_grab_mode) => {
+                    let grab = cursor_grab_mode != CursorGrabMode::None;
+                    if let Err(e) = window.set_cursor_grab(grab) {
+                        warn!("Failed to set cursor grab to {:?}: {}", cursor_grab_mode, e);
+                    }
+
+                    window.set_cursor_visible(cursor_grab_mode != CursorGrabMode::Locked);
+                }
+                WindowCommand::SetCursorPosition(position) => {
+                    if let Err(e) = window.set_cursor_position(position) {
+                        warn!("Failed to set cursor position to {:?}: {}", position, e);
+                    }
+                }
+            }
+        }
+    }
 }
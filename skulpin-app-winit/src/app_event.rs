@@ -0,0 +1,59 @@
+//! Allows other threads to send events to a running app
+
+use crate::winit;
+
+/// Events sent to the event loop by the app
+pub(crate) enum AppEvent<U> {
+    /// An event sent with an `AppEventSender`, passed to `AppHandler::user_event`
+    User(U),
+
+    /// A task was woken and the task executor needs to be polled
+    WakeTasks,
+}
+
+/// Returned by `AppEventSender::send` if the app has already terminated
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SendEventError;
+
+impl std::error::Error for SendEventError {}
+
+impl core::fmt::Display for SendEventError {
+    fn fmt(
+        &self,
+        fmt: &mut core::fmt::Formatter,
+    ) -> core::fmt::Result {
+        write!(fmt, "The app's event loop has terminated")
+    }
+}
+
+/// Sends events to the app, waking it if necessary. The events are passed to
+/// `AppHandler::user_event` on the main thread. Obtained with `AppBuilder::event_sender`.
+///
+/// This can be cloned and sent to other threads if the event type is `Send`.
+pub struct AppEventSender<U: 'static> {
+    event_loop_proxy: winit::event_loop::EventLoopProxy<AppEvent<U>>,
+}
+
+impl<U: 'static> Clone for AppEventSender<U> {
+    fn clone(&self) -> Self {
+        AppEventSender {
+            event_loop_proxy: self.event_loop_proxy.clone(),
+        }
+    }
+}
+
+impl<U: 'static> AppEventSender<U> {
+    pub(crate) fn new(event_loop_proxy: winit::event_loop::EventLoopProxy<AppEvent<U>>) -> Self {
+        AppEventSender { event_loop_proxy }
+    }
+
+    /// Send an event to the app. This fails if the app has already terminated.
+    pub fn send(
+        &self,
+        event: U,
+    ) -> Result<(), SendEventError> {
+        self.event_loop_proxy
+            .send_event(AppEvent::User(event))
+            .map_err(|_| SendEventError)
+    }
+}
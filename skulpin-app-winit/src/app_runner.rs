@@ -0,0 +1,425 @@
+//! Drives an `AppHandler` one event at a time. This is used by `App`, and can also be used directly
+//! to embed a skulpin app in an event loop and window that are owned by something else.
+
+use crate::winit;
+
+use super::app::AppHandler;
+use super::app::AppError;
+use super::app::AppUpdateArgs;
+use super::app::AppDrawArgs;
+use super::app_control::AppControl;
+use super::input_state::InputState;
+use super::input_state::InputConfig;
+use super::time_state::TimeState;
+use super::util::PeriodicEvent;
+use super::task::TaskExecutor;
+
+use crate::winit::window::Window;
+use skulpin_renderer::Renderer;
+use skulpin_renderer::RendererBuilder;
+use skulpin_renderer::SkulpinError;
+use skulpin_renderer::CoordinateSystemHelper;
+use crate::rafx::api::RafxExtents2D;
+
+use std::marker::PhantomData;
+
+/// Drives an `AppHandler` using a window that is owned by the caller. This does the same work as
+/// `App::run`, but leaves the event loop to the caller.
+///
+/// Pass every event from the event loop to `handle_event`. Then either call `update` when winit
+/// sends `Event::MainEventsCleared` and `draw` when it sends `Event::RedrawRequested` for the
+/// window, or call `tick` to do both at once.
+pub struct AppRunner<T: AppHandler<U>, U = ()> {
+    // These are optional so that they can be dropped in a specific order when the app shuts down
+    app_handler: Option<T>,
+    renderer: Option<Renderer>,
+
+    app_control: AppControl,
+    time_state: TimeState,
+    input_state: InputState,
+    task_executor: TaskExecutor,
+
+    // To print fps once per second
+    print_fps_event: PeriodicEvent,
+
+    // winit reports minimizing as a resize to zero, so track it to detect restoring
+    is_minimized: bool,
+
+    // Set if the app is terminating because of an error
+    fatal_error: Option<AppError>,
+
+    // Set when the renderer is recreated after the device or surface was lost, and cleared once a
+    // frame is drawn. If the renderer is lost again before then, the error is fatal.
+    is_recovering_renderer: bool,
+
+    phantom_data: PhantomData<U>,
+}
+
+impl<T: AppHandler<U>, U> AppRunner<T, U> {
+    /// Create the renderer for the given window. If this fails, the app handler is notified of the
+    /// error and shut down
+    pub fn new(
+        mut app_handler: T,
+        window: &Window,
+        input_config: InputConfig,
+        renderer_builder: RendererBuilder,
+    ) -> Result<Self, AppError> {
+        let window_size = window.inner_size();
+        let window_extents = RafxExtents2D {
+            width: window_size.width,
+            height: window_size.height,
+        };
+
+        let renderer = match renderer_builder.build(window, window_extents) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                warn!("Passing RendererBuilder::build() error to app {}", e);
+
+                let app_error = e.into();
+                app_handler.fatal_error(&app_error);
+                app_handler.shutdown();
+                return Err(app_error);
+            }
+        };
+
+        Ok(AppRunner {
+            app_handler: Some(app_handler),
+            renderer: Some(renderer),
+            app_control: AppControl::default(),
+            time_state: TimeState::new(),
+            input_state: InputState::with_config(window, input_config),
+            task_executor: TaskExecutor::new(),
+            print_fps_event: PeriodicEvent::default(),
+            is_minimized: false,
+            fatal_error: None,
+            is_recovering_renderer: false,
+            phantom_data: PhantomData,
+        })
+    }
+
+    // Accessors
+
+    /// The app handler, or None if the app has shut down
+    pub fn app_handler(&self) -> Option<&T> {
+        self.app_handler.as_ref()
+    }
+
+    /// The app handler, or None if the app has shut down
+    pub fn app_handler_mut(&mut self) -> Option<&mut T> {
+        self.app_handler.as_mut()
+    }
+
+    pub fn app_control(&self) -> &AppControl {
+        &self.app_control
+    }
+
+    pub fn app_control_mut(&mut self) -> &mut AppControl {
+        &mut self.app_control
+    }
+
+    pub fn input_state(&self) -> &InputState {
+        &self.input_state
+    }
+
+    pub fn time_state(&self) -> &TimeState {
+        &self.time_state
+    }
+
+    /// The executor for tasks spawned by the app. Use `TaskExecutor::set_event_loop_waker` to wake
+    /// the event loop when a task is ready to make progress.
+    pub fn task_executor_mut(&mut self) -> &mut TaskExecutor {
+        &mut self.task_executor
+    }
+
+    /// The error that caused the app to terminate, if any
+    pub fn fatal_error(&self) -> Option<&AppError> {
+        self.fatal_error.as_ref()
+    }
+
+    /// Takes the error that caused the app to terminate, if any
+    pub fn take_fatal_error(&mut self) -> Option<AppError> {
+        self.fatal_error.take()
+    }
+
+    /// Returns true if the app has asked to terminate, or has terminated because of an error. The
+    /// caller should stop calling `update`/`draw` and call `shutdown`.
+    pub fn should_terminate(&self) -> bool {
+        self.app_control.should_terminate_process()
+    }
+
+    //
+    // Handlers for significant events
+    //
+
+    /// Process a single event from winit. Window events for other windows are ignored. This does
+    /// not update or draw the app.
+    ///
+    /// `Event::UserEvent` is not passed to the app since the event loop may belong to the caller
+    /// and have a different event type. Use `user_event` to pass events to the app.
+    ///
+    /// When winit sends `Event::LoopDestroyed`, the app is shut down.
+    pub fn handle_event<E>(
+        &mut self,
+        window: &Window,
+        event: &winit::event::Event<E>,
+        window_target: &winit::event_loop::EventLoopWindowTarget<E>,
+    ) {
+        if let winit::event::Event::WindowEvent { window_id, .. } = event {
+            if *window_id != window.id() {
+                return;
+            }
+        }
+
+        self.input_state.handle_winit_event(event, window_target);
+
+        if let winit::event::Event::WindowEvent { event, .. } = event {
+            self.refresh_monitors(window, event);
+        }
+
+        match event {
+            winit::event::Event::WindowEvent { event, .. } => self.dispatch_window_event(event),
+            winit::event::Event::LoopDestroyed => self.shutdown(),
+            _ => {}
+        }
+    }
+
+    /// Pass an event to the app's `AppHandler::user_event`
+    pub fn user_event(
+        &mut self,
+        event: U,
+    ) {
+        if let Some(app_handler) = &mut self.app_handler {
+            app_handler.user_event(&mut self.app_control, event);
+        }
+    }
+
+    /// Update the app. This also applies any changes to the window the app requested and marks the
+    /// start of the next frame for input handling.
+    pub fn update(
+        &mut self,
+        window: &Window,
+    ) {
+        let app_handler = match &mut self.app_handler {
+            Some(app_handler) => app_handler,
+            None => return,
+        };
+
+        self.time_state.update();
+
+        if self.print_fps_event.try_take_event(
+            self.time_state.current_instant(),
+            std::time::Duration::from_secs(1),
+        ) {
+            debug!("fps: {}", self.time_state.updates_per_second());
+        }
+
+        // Make progress on any tasks that were woken since the last update
+        self.task_executor.poll();
+
+        let window_size = window.inner_size();
+        let window_extents = RafxExtents2D {
+            width: window_size.width,
+            height: window_size.height,
+        };
+
+        // Use the same coordinate system the renderer will apply when drawing
+        let mut coordinate_system_helper =
+            CoordinateSystemHelper::new(window_extents, window.scale_factor());
+        if let Some(renderer) = &self.renderer {
+            coordinate_system_helper =
+                coordinate_system_helper.with_coordinate_system(renderer.coordinate_system);
+        }
+
+        app_handler.update(AppUpdateArgs {
+            app_control: &mut self.app_control,
+            input_state: &self.input_state,
+            time_state: &self.time_state,
+            task_executor: &mut self.task_executor,
+            coordinate_system_helper,
+        });
+
+        // Apply any changes to the window the app requested during the update
+        self.app_control.apply_window_commands(window);
+
+        // Call this to mark the start of the next frame (i.e. "key just down" will return false)
+        self.input_state.end_frame();
+    }
+
+    /// Draw the app to the window. If the device or surface is lost, the renderer is recreated and
+    /// the app is notified with `AppHandler::renderer_recreated`. If the coordinate system can't be
+    /// used at the current window size, the frame is skipped. If drawing fails otherwise, the app
+    /// is notified of the error and will terminate.
+    pub fn draw(
+        &mut self,
+        window: &Window,
+    ) {
+        let (app_handler, renderer) = match (&mut self.app_handler, &mut self.renderer) {
+            (Some(app_handler), Some(renderer)) => (app_handler, renderer),
+            _ => return,
+        };
+
+        let window_size = window.inner_size();
+        let window_extents = RafxExtents2D {
+            width: window_size.width,
+            height: window_size.height,
+        };
+
+        let app_control = &self.app_control;
+        let input_state = &self.input_state;
+        let time_state = &self.time_state;
+
+        let result = renderer.draw(
+            window_extents,
+            window.scale_factor(),
+            |canvas, coordinate_system_helper| {
+                app_handler.draw(AppDrawArgs {
+                    app_control,
+                    input_state,
+                    time_state,
+                    canvas,
+                    coordinate_system_helper,
+                });
+            },
+        );
+
+        let e = match result {
+            Ok(()) => {
+                self.is_recovering_renderer = false;
+                return;
+            }
+            Err(e) => e,
+        };
+
+        // The coordinate system can't be used at the current window size (i.e. an empty visible
+        // range). Nothing was drawn, but the next frame may succeed.
+        if let SkulpinError::CoordinateSystem(e) = &e {
+            warn!("Skipping frame, the coordinate system can't be used: {}", e);
+            return;
+        }
+
+        let e = if e.requires_recreate() && !self.is_recovering_renderer {
+            warn!("Recreating the renderer after Renderer::draw() error {}", e);
+            self.is_recovering_renderer = true;
+
+            match renderer.recreate(window, window_extents) {
+                Ok(()) => {
+                    app_handler.renderer_recreated(&mut self.app_control);
+                    return;
+                }
+                Err(e) => e,
+            }
+        } else {
+            e
+        };
+
+        warn!("Passing Renderer::draw() error to app {}", e);
+
+        let app_error = e.into();
+        app_handler.fatal_error(&app_error);
+        self.fatal_error = Some(app_error);
+        self.app_control.enqueue_terminate_process();
+    }
+
+    /// Update and then draw the app
+    pub fn tick(
+        &mut self,
+        window: &Window,
+    ) {
+        self.update(window);
+        self.draw(window);
+    }
+
+    /// Notify the app that it is shutting down and drop it, followed by the renderer. This is
+    /// called automatically when winit sends `Event::LoopDestroyed`, and does nothing if the app
+    /// has already shut down.
+    pub fn shutdown(&mut self) {
+        if let Some(mut app_handler) = self.app_handler.take() {
+            debug!("Shutting down app");
+            app_handler.shutdown();
+        }
+
+        // Drop the renderer after the app since the app may be holding GPU resources
+        self.renderer = None;
+    }
+
+    /// Re-reads the monitors when the window may have moved to a different monitor, notifying the
+    /// app handler if it did. winit has no event for monitors being connected or disconnected, so
+    /// the monitors are also refreshed when the window gains focus. Moving the window only checks
+    /// the monitor it is on, since winit sends `Moved` continuously while the window is dragged.
+    fn refresh_monitors(
+        &mut self,
+        window: &Window,
+        event: &winit::event::WindowEvent,
+    ) {
+        use winit::event::WindowEvent;
+
+        let monitor_changed = match event {
+            WindowEvent::Moved(_) => self.input_state.refresh_current_monitor(window),
+            WindowEvent::ScaleFactorChanged { .. } | WindowEvent::Focused(true) => {
+                self.input_state.refresh_monitors(window)
+            }
+            _ => return,
+        };
+
+        if !monitor_changed {
+            return;
+        }
+
+        if let (Some(app_handler), Some(monitor)) =
+            (&mut self.app_handler, self.input_state.current_monitor())
+        {
+            trace!("monitor changed {:?}", monitor.name);
+            app_handler.monitor_changed(&mut self.app_control, monitor);
+        }
+    }
+
+    /// Passes window events that the app may be interested in to the app handler
+    fn dispatch_window_event(
+        &mut self,
+        event: &winit::event::WindowEvent,
+    ) {
+        use winit::event::WindowEvent;
+
+        let app_handler = match &mut self.app_handler {
+            Some(app_handler) => app_handler,
+            None => return,
+        };
+
+        let app_control = &mut self.app_control;
+
+        match event {
+            WindowEvent::CloseRequested => {
+                trace!("close requested");
+                if app_handler.close_requested(app_control) {
+                    app_control.enqueue_terminate_process();
+                } else {
+                    debug!("close request was vetoed by the app");
+                }
+            }
+            WindowEvent::Resized(inner_size) => {
+                if inner_size.width == 0 || inner_size.height == 0 {
+                    if !self.is_minimized {
+                        self.is_minimized = true;
+                        app_handler.minimized(app_control);
+                    }
+                } else {
+                    if self.is_minimized {
+                        self.is_minimized = false;
+                        app_handler.restored(app_control);
+                    }
+
+                    app_handler.resized(app_control, *inner_size);
+                }
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                app_handler.scale_factor_changed(app_control, *scale_factor)
+            }
+            WindowEvent::Focused(true) => app_handler.focus_gained(app_control),
+            WindowEvent::Focused(false) => app_handler.focus_lost(app_control),
+            WindowEvent::HoveredFile(path) => app_handler.file_hovered(app_control, path),
+            WindowEvent::HoveredFileCancelled => app_handler.file_hover_cancelled(app_control),
+            WindowEvent::DroppedFile(path) => app_handler.file_dropped(app_control, path),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! Access to the system clipboard. The clipboard is available from `AppControl::clipboard`.
+//!
+//! The system clipboard requires the `clipboard` feature. Without it, or if the system clipboard
+//! can't be opened, an in-memory clipboard that is private to the app is used instead.
+//!
+//! The native Wayland clipboard is not supported. Wayland sessions can only use the system
+//! clipboard through XWayland, and fall back to the in-memory clipboard (with a warning) if
+//! XWayland is not running.
+
+use crate::skia_safe;
+
+/// Returned when the clipboard can't be read or written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardError {
+    /// The clipboard is empty or does not contain the requested type of content
+    ContentNotAvailable,
+
+    /// The clipboard backend does not support the requested type of content
+    NotSupported,
+
+    /// An image could not be converted to or from the clipboard's format
+    ImageConversionFailed,
+
+    /// An error reported by the system clipboard
+    Backend(String),
+}
+
+impl std::error::Error for ClipboardError {}
+
+impl core::fmt::Display for ClipboardError {
+    fn fmt(
+        &self,
+        fmt: &mut core::fmt::Formatter,
+    ) -> core::fmt::Result {
+        match self {
+            ClipboardError::ContentNotAvailable => {
+                write!(fmt, "The clipboard does not contain the requested content")
+            }
+            ClipboardError::NotSupported => {
+                write!(fmt, "The clipboard does not support the requested content")
+            }
+            ClipboardError::ImageConversionFailed => {
+                write!(fmt, "The image could not be converted")
+            }
+            ClipboardError::Backend(e) => write!(fmt, "Clipboard error: {}", e),
+        }
+    }
+}
+
+/// An image stored on the clipboard as unpremultiplied 8-bit RGBA pixels, row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+/// Implemented by clipboard implementations. Use `AppControl::set_clipboard_backend` to replace the
+/// default backend, i.e. with a `MemoryClipboard` in tests.
+pub trait ClipboardBackend {
+    fn get_text(&mut self) -> Result<String, ClipboardError>;
+
+    fn set_text(
+        &mut self,
+        text: String,
+    ) -> Result<(), ClipboardError>;
+
+    fn get_image(&mut self) -> Result<ClipboardImage, ClipboardError>;
+
+    fn set_image(
+        &mut self,
+        image: ClipboardImage,
+    ) -> Result<(), ClipboardError>;
+}
+
+/// A clipboard that stores its contents in memory. Content is not shared with other applications.
+/// This is useful for tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryClipboard {
+    text: Option<String>,
+    image: Option<ClipboardImage>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+// Like most system clipboards, setting one type of content replaces the other
+impl ClipboardBackend for MemoryClipboard {
+    fn get_text(&mut self) -> Result<String, ClipboardError> {
+        self.text.clone().ok_or(ClipboardError::ContentNotAvailable)
+    }
+
+    fn set_text(
+        &mut self,
+        text: String,
+    ) -> Result<(), ClipboardError> {
+        self.text = Some(text);
+        self.image = None;
+        Ok(())
+    }
+
+    fn get_image(&mut self) -> Result<ClipboardImage, ClipboardError> {
+        self.image
+            .clone()
+            .ok_or(ClipboardError::ContentNotAvailable)
+    }
+
+    fn set_image(
+        &mut self,
+        image: ClipboardImage,
+    ) -> Result<(), ClipboardError> {
+        self.image = Some(image);
+        self.text = None;
+        Ok(())
+    }
+}
+
+/// The system clipboard, implemented with arboard. On Linux, the X11 clipboard is used, which
+/// Wayland sessions share through XWayland. Opening it fails on Wayland if XWayland is not running.
+#[cfg(feature = "clipboard")]
+pub struct SystemClipboard {
+    clipboard: arboard::Clipboard,
+}
+
+#[cfg(feature = "clipboard")]
+impl SystemClipboard {
+    pub fn new() -> Result<Self, ClipboardError> {
+        // arboard is built without its Wayland backend, so only the X11 clipboard is available
+        #[cfg(all(
+            unix,
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        ))]
+        {
+            if std::env::var_os("WAYLAND_DISPLAY").is_some()
+                && std::env::var_os("DISPLAY").is_none()
+            {
+                return Err(ClipboardError::Backend(
+                    "The native Wayland clipboard is not supported, XWayland is required"
+                        .to_string(),
+                ));
+            }
+        }
+
+        let clipboard = arboard::Clipboard::new().map_err(ClipboardError::from)?;
+        Ok(SystemClipboard { clipboard })
+    }
+}
+
+#[cfg(feature = "clipboard")]
+impl From<arboard::Error> for ClipboardError {
+    fn from(error: arboard::Error) -> Self {
+        match error {
+            arboard::Error::ContentNotAvailable => ClipboardError::ContentNotAvailable,
+            arboard::Error::ClipboardNotSupported => ClipboardError::NotSupported,
+            arboard::Error::ConversionFailure => ClipboardError::ImageConversionFailed,
+            e => ClipboardError::Backend(e.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "clipboard")]
+impl ClipboardBackend for SystemClipboard {
+    fn get_text(&mut self) -> Result<String, ClipboardError> {
+        Ok(self.clipboard.get_text()?)
+    }
+
+    fn set_text(
+        &mut self,
+        text: String,
+    ) -> Result<(), ClipboardError> {
+        Ok(self.clipboard.set_text(text)?)
+    }
+
+    fn get_image(&mut self) -> Result<ClipboardImage, ClipboardError> {
+        let image = self.clipboard.get_image()?;
+        Ok(ClipboardImage {
+            width: image.width,
+            height: image.height,
+            rgba: image.bytes.into_owned(),
+        })
+    }
+
+    fn set_image(
+        &mut self,
+        image: ClipboardImage,
+    ) -> Result<(), ClipboardError> {
+        Ok(self.clipboard.set_image(arboard::ImageData {
+            width: image.width,
+            height: image.height,
+            bytes: image.rgba.into(),
+        })?)
+    }
+}
+
+/// Reads and writes the clipboard. Images are converted to and from `skia_safe::Image`, or PNG
+/// files encoded with Skia.
+#[derive(Default)]
+pub struct Clipboard {
+    // Created when the clipboard is first used since opening the system clipboard may connect to
+    // the display server
+    backend: Option<Box<dyn ClipboardBackend>>,
+}
+
+impl Clipboard {
+    /// Create a clipboard that uses the given backend
+    pub fn with_backend<B: ClipboardBackend + 'static>(backend: B) -> Self {
+        Clipboard {
+            backend: Some(Box::new(backend)),
+        }
+    }
+
+    /// The backend used to access the clipboard, created if necessary
+    pub fn backend(&mut self) -> &mut dyn ClipboardBackend {
+        self.backend
+            .get_or_insert_with(Self::create_default_backend)
+            .as_mut()
+    }
+
+    #[cfg(feature = "clipboard")]
+    fn create_default_backend() -> Box<dyn ClipboardBackend> {
+        match SystemClipboard::new() {
+            Ok(clipboard) => Box::new(clipboard),
+            Err(e) => {
+                warn!(
+                    "Failed to open the system clipboard, using an in-memory clipboard instead: {}",
+                    e
+                );
+                Box::new(MemoryClipboard::new())
+            }
+        }
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    fn create_default_backend() -> Box<dyn ClipboardBackend> {
+        debug!("The clipboard feature is not enabled, using an in-memory clipboard");
+        Box::new(MemoryClipboard::new())
+    }
+
+    /// Returns the text on the clipboard
+    pub fn get_text(&mut self) -> Result<String, ClipboardError> {
+        self.backend().get_text()
+    }
+
+    /// Place text on the clipboard
+    pub fn set_text<T: Into<String>>(
+        &mut self,
+        text: T,
+    ) -> Result<(), ClipboardError> {
+        self.backend().set_text(text.into())
+    }
+
+    /// Returns the image on the clipboard as raw RGBA pixels
+    pub fn get_image_rgba(&mut self) -> Result<ClipboardImage, ClipboardError> {
+        self.backend().get_image()
+    }
+
+    /// Place raw RGBA pixels on the clipboard
+    pub fn set_image_rgba(
+        &mut self,
+        image: ClipboardImage,
+    ) -> Result<(), ClipboardError> {
+        if image.rgba.len() != image.width * image.height * 4 {
+            return Err(ClipboardError::ImageConversionFailed);
+        }
+
+        self.backend().set_image(image)
+    }
+
+    /// Returns the image on the clipboard as a raster image
+    pub fn get_image(&mut self) -> Result<skia_safe::Image, ClipboardError> {
+        let image = self.get_image_rgba()?;
+        let image_info = rgba_image_info(image.width, image.height);
+        let row_bytes = image.width * 4;
+
+        skia_safe::Image::from_raster_data(
+            &image_info,
+            skia_safe::Data::new_copy(&image.rgba),
+            row_bytes,
+        )
+        .ok_or(ClipboardError::ImageConversionFailed)
+    }
+
+    /// Place an image on the clipboard. GPU-backed images (i.e. a snapshot of a GPU surface) can't
+    /// be read without the GPU context, so convert them with `Image::to_raster_image` first.
+    pub fn set_image(
+        &mut self,
+        image: &skia_safe::Image,
+    ) -> Result<(), ClipboardError> {
+        let width = image.width() as usize;
+        let height = image.height() as usize;
+        let image_info = rgba_image_info(width, height);
+        let row_bytes = width * 4;
+
+        let mut rgba = vec![0; row_bytes * height];
+        if !image.read_pixels(
+            &image_info,
+            &mut rgba,
+            row_bytes,
+            (0, 0),
+            skia_safe::image::CachingHint::Disallow,
+        ) {
+            return Err(ClipboardError::ImageConversionFailed);
+        }
+
+        self.backend().set_image(ClipboardImage {
+            width,
+            height,
+            rgba,
+        })
+    }
+
+    /// Returns the image on the clipboard encoded as a PNG file
+    pub fn get_png(&mut self) -> Result<Vec<u8>, ClipboardError> {
+        let image = self.get_image()?;
+        image
+            .encode_to_data(skia_safe::EncodedImageFormat::PNG)
+            .map(|data| data.as_bytes().to_vec())
+            .ok_or(ClipboardError::ImageConversionFailed)
+    }
+
+    /// Decode a PNG (or any other format Skia can decode) and place it on the clipboard
+    pub fn set_png(
+        &mut self,
+        encoded: &[u8],
+    ) -> Result<(), ClipboardError> {
+        let image = skia_safe::Image::from_encoded(skia_safe::Data::new_copy(encoded))
+            .ok_or(ClipboardError::ImageConversionFailed)?;
+
+        self.set_image(&image)
+    }
+}
+
+fn rgba_image_info(
+    width: usize,
+    height: usize,
+) -> skia_safe::ImageInfo {
+    skia_safe::ImageInfo::new(
+        (width as i32, height as i32),
+        skia_safe::ColorType::RGBA8888,
+        skia_safe::AlphaType::Unpremul,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_clipboard() -> Clipboard {
+        Clipboard::with_backend(MemoryClipboard::new())
+    }
+
+    // A 2x2 image with a different color in each pixel. It is opaque so that converting to and
+    // from premultiplied alpha doesn't change the values.
+    fn test_image() -> ClipboardImage {
+        ClipboardImage {
+            width: 2,
+            height: 2,
+            rgba: vec![
+                255, 0, 0, 255, //
+                0, 255, 0, 255, //
+                0, 0, 255, 255, //
+                255, 255, 255, 255,
+            ],
+        }
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let mut clipboard = memory_clipboard();
+        assert_eq!(
+            clipboard.get_text(),
+            Err(ClipboardError::ContentNotAvailable)
+        );
+
+        clipboard.set_text("hello clipboard").unwrap();
+        assert_eq!(clipboard.get_text().unwrap(), "hello clipboard");
+    }
+
+    #[test]
+    fn setting_an_image_replaces_text() {
+        let mut clipboard = memory_clipboard();
+        clipboard.set_text("hello").unwrap();
+        clipboard.set_image_rgba(test_image()).unwrap();
+
+        assert_eq!(
+            clipboard.get_text(),
+            Err(ClipboardError::ContentNotAvailable)
+        );
+        assert_eq!(clipboard.get_image_rgba().unwrap(), test_image());
+    }
+
+    #[test]
+    fn rgba_size_mismatch_is_rejected() {
+        let mut clipboard = memory_clipboard();
+        let mut image = test_image();
+        image.rgba.pop();
+
+        assert_eq!(
+            clipboard.set_image_rgba(image),
+            Err(ClipboardError::ImageConversionFailed)
+        );
+    }
+
+    #[test]
+    fn skia_image_round_trip() {
+        let mut clipboard = memory_clipboard();
+        clipboard.set_image_rgba(test_image()).unwrap();
+
+        let image = clipboard.get_image().unwrap();
+        assert_eq!((image.width(), image.height()), (2, 2));
+
+        clipboard.set_image(&image).unwrap();
+        assert_eq!(clipboard.get_image_rgba().unwrap(), test_image());
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let mut clipboard = memory_clipboard();
+        clipboard.set_image_rgba(test_image()).unwrap();
+
+        let png = clipboard.get_png().unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let mut other = memory_clipboard();
+        other.set_png(&png).unwrap();
+        assert_eq!(other.get_image_rgba().unwrap(), test_image());
+    }
+
+    #[test]
+    fn invalid_png_is_rejected() {
+        let mut clipboard = memory_clipboard();
+        assert_eq!(
+            clipboard.set_png(b"not a png"),
+            Err(ClipboardError::ImageConversionFailed)
+        );
+    }
+}
@@ -1,12 +1,14 @@
 //! Handles input tracking and provides an easy way to detect clicks, dragging, etc.
 
 use crate::winit;
+use crate::skia_safe;
 
 // Re-export winit types
 pub use winit::event::VirtualKeyCode;
 pub use winit::event::MouseButton;
 pub use winit::event::MouseScrollDelta;
 pub use winit::event::ElementState;
+pub use winit::event::TouchPhase;
 pub use winit::dpi::LogicalSize;
 pub use winit::dpi::PhysicalSize;
 pub use winit::dpi::LogicalPosition;
@@ -14,16 +16,18 @@ pub use winit::dpi::PhysicalPosition;
 pub use winit::dpi::Size;
 pub use winit::dpi::Position;
 
-use super::AppControl;
 use crate::winit::window::Window;
 
+use super::monitor;
+use super::monitor::MonitorInfo;
+
 /// Encapsulates the state of a mouse drag
 #[derive(Copy, Clone, Debug)]
 pub struct MouseDragState {
-    /// Logical position where the drag began
+    /// Physical position where the drag began
     pub begin_position: PhysicalPosition<f64>,
 
-    /// Logical position where the drag ended
+    /// Physical position where the drag ended
     pub end_position: PhysicalPosition<f64>,
 
     /// Amount of mouse movement in the previous frame
@@ -33,17 +37,169 @@ pub struct MouseDragState {
     pub accumulated_frame_delta: PhysicalPosition<f64>,
 }
 
+impl MouseDragState {
+    /// Converts the drag into canvas coordinates. `canvas_matrix` maps canvas coordinates to the
+    /// surface, for example as returned by `CoordinateSystemHelper::coordinate_system_matrix`.
+    /// Returns None if the matrix can't be inverted.
+    pub fn to_canvas(
+        &self,
+        canvas_matrix: &skia_safe::Matrix,
+    ) -> Option<CanvasMouseDragState> {
+        let inverse = canvas_matrix.invert()?;
+
+        // Deltas are mapped as vectors so that they are not affected by translation
+        Some(CanvasMouseDragState {
+            begin_position: inverse
+                .map_xy(self.begin_position.x as f32, self.begin_position.y as f32),
+            end_position: inverse.map_xy(self.end_position.x as f32, self.end_position.y as f32),
+            previous_frame_delta: inverse.map_vector((
+                self.previous_frame_delta.x as f32,
+                self.previous_frame_delta.y as f32,
+            )),
+            accumulated_frame_delta: inverse.map_vector((
+                self.accumulated_frame_delta.x as f32,
+                self.accumulated_frame_delta.y as f32,
+            )),
+        })
+    }
+}
+
+/// Encapsulates the state of a mouse drag in canvas coordinates. See `MouseDragState::to_canvas`
+#[derive(Copy, Clone, Debug)]
+pub struct CanvasMouseDragState {
+    /// Canvas position where the drag began
+    pub begin_position: skia_safe::Point,
+
+    /// Canvas position where the drag ended
+    pub end_position: skia_safe::Point,
+
+    /// Amount of mouse movement in the previous frame
+    pub previous_frame_delta: skia_safe::Vector,
+
+    /// Amount of mouse movement in total
+    pub accumulated_frame_delta: skia_safe::Vector,
+}
+
+/// Thresholds used to distinguish clicks, drags, multi-clicks and long presses
+#[derive(Copy, Clone, Debug)]
+pub struct InputConfig {
+    /// Distance in logical units that the mouse has to be dragged to be considered a drag rather
+    /// than a click
+    pub drag_distance: f64,
+
+    /// Maximum time between clicks for them to be counted as a multi-click (i.e. double-click)
+    pub multi_click_interval: std::time::Duration,
+
+    /// Maximum distance in logical units between clicks for them to be counted as a multi-click
+    pub multi_click_radius: f64,
+
+    /// Time a button must be held without dragging to be considered a long press
+    pub long_press_duration: std::time::Duration,
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        InputConfig {
+            drag_distance: 2.0,
+            multi_click_interval: std::time::Duration::from_millis(500),
+            multi_click_radius: 4.0,
+            long_press_duration: std::time::Duration::from_millis(500),
+        }
+    }
+}
+
+/// A single sample of pointer motion, including pen/stylus pressure and tilt when the platform
+/// reports them. Every motion event is recorded, so several samples may arrive within one frame.
+///
+/// winit does not report which end of a pen is in use, so the eraser can't be distinguished from
+/// the tip.
+#[derive(Copy, Clone, Debug)]
+pub struct PenSample {
+    /// Physical position of the sample
+    pub position: PhysicalPosition<f64>,
+
+    /// Pressure in the range 0.0 to 1.0, or None if the device does not report pressure (i.e. a
+    /// mouse)
+    pub pressure: Option<f64>,
+
+    /// Tilt of the pen in radians. 0 means the pen is parallel to the surface and PI/2 means it
+    /// is perpendicular. None if the device does not report it.
+    pub tilt: Option<f64>,
+
+    /// True if the pen is touching the surface (or for a mouse, the left button is down)
+    pub in_contact: bool,
+
+    /// Time the sample was received
+    pub time: std::time::Instant,
+}
+
+/// A discrete input event. Unlike the polled state in `InputState`, these are never merged, so
+/// multiple presses within a single frame and their exact order can be observed.
+#[derive(Copy, Clone, Debug)]
+pub enum InputEvent {
+    /// A key went down. `is_repeat` is true if the key was already down (i.e. OS key repeat)
+    KeyDown {
+        key: VirtualKeyCode,
+        is_repeat: bool,
+    },
+
+    /// A key went up
+    KeyUp { key: VirtualKeyCode },
+
+    /// A unicode character was received. This accounts for keyboard layout, shift, etc. and is
+    /// generally what should be used for text entry.
+    ReceivedCharacter(char),
+
+    /// A mouse button went down
+    MouseButtonDown(MouseButton),
+
+    /// A mouse button went up
+    MouseButtonUp(MouseButton),
+
+    /// The mouse moved. The new position is stored in `TimedInputEvent::position`
+    MouseMoved,
+
+    /// The mouse wheel was scrolled
+    MouseWheel(MouseScrollDelta),
+
+    /// Raw, unaccelerated mouse motion. This is reported even if the cursor is grabbed and unable
+    /// to move. Units are device-specific.
+    MouseMotion { delta: (f64, f64) },
+
+    /// A touch (or pen) event occurred
+    Touch { id: u64, phase: TouchPhase },
+}
+
+/// An input event along with when it occurred and where the mouse was at the time
+#[derive(Copy, Clone, Debug)]
+pub struct TimedInputEvent {
+    /// The event that occurred
+    pub event: InputEvent,
+
+    /// Mouse position when the event occurred (or for touch events, the touch location)
+    pub position: PhysicalPosition<f64>,
+
+    /// Time the event was received
+    pub time: std::time::Instant,
+}
+
 /// State of input devices. This is maintained by processing events from winit
 pub struct InputState {
+    config: InputConfig,
+
     window_size: PhysicalSize<u32>,
     scale_factor: f64,
 
+    monitors: Vec<MonitorInfo>,
+    current_monitor: Option<MonitorInfo>,
+
     key_is_down: [bool; Self::KEYBOARD_BUTTON_COUNT],
     key_just_down: [bool; Self::KEYBOARD_BUTTON_COUNT],
     key_just_up: [bool; Self::KEYBOARD_BUTTON_COUNT],
 
     mouse_position: PhysicalPosition<f64>,
     mouse_wheel_delta: MouseScrollDelta,
+    mouse_motion: (f64, f64),
     mouse_button_is_down: [bool; Self::MOUSE_BUTTON_COUNT],
     mouse_button_just_down: [Option<PhysicalPosition<f64>>; Self::MOUSE_BUTTON_COUNT],
     mouse_button_just_up: [Option<PhysicalPosition<f64>>; Self::MOUSE_BUTTON_COUNT],
@@ -52,9 +208,23 @@ pub struct InputState {
 
     mouse_button_went_down_position: [Option<PhysicalPosition<f64>>; Self::MOUSE_BUTTON_COUNT],
     mouse_button_went_up_position: [Option<PhysicalPosition<f64>>; Self::MOUSE_BUTTON_COUNT],
+    mouse_button_went_down_time: [Option<std::time::Instant>; Self::MOUSE_BUTTON_COUNT],
+
+    // Number of clicks in the current multi-click sequence and where/when the last one happened
+    mouse_button_click_count: [u32; Self::MOUSE_BUTTON_COUNT],
+    mouse_button_last_click:
+        [Option<(PhysicalPosition<f64>, std::time::Instant)>; Self::MOUSE_BUTTON_COUNT],
+
+    mouse_long_press_in_progress: [bool; Self::MOUSE_BUTTON_COUNT],
+    mouse_long_press_just_started: [Option<PhysicalPosition<f64>>; Self::MOUSE_BUTTON_COUNT],
 
     mouse_drag_in_progress: [Option<MouseDragState>; Self::MOUSE_BUTTON_COUNT],
     mouse_drag_just_finished: [Option<MouseDragState>; Self::MOUSE_BUTTON_COUNT],
+
+    pen_samples: Vec<PenSample>,
+    latest_pen_sample: Option<PenSample>,
+
+    events: Vec<TimedInputEvent>,
 }
 
 impl InputState {
@@ -64,31 +234,65 @@ impl InputState {
 
     /// Number of mouse buttons we will track. Any button with a higher index will be ignored.
     pub const MOUSE_BUTTON_COUNT: usize = 7;
-
-    /// Distance in LogicalPosition units that the mouse has to be dragged to be considered a drag
-    /// rather than a click
-    const MIN_DRAG_DISTANCE: f64 = 2.0;
 }
 
 impl InputState {
     /// Create a new input state to track the given window
     pub fn new(window: &Window) -> InputState {
+        Self::with_config(window, InputConfig::default())
+    }
+
+    /// Create a new input state to track the given window, using the given thresholds for
+    /// detecting drags, multi-clicks, and long presses
+    pub fn with_config(
+        window: &Window,
+        config: InputConfig,
+    ) -> InputState {
+        let (monitors, current_monitor) = monitor::enumerate_monitors(window);
+        Self::with_window_properties(
+            config,
+            window.inner_size(),
+            window.scale_factor(),
+            monitors,
+            current_monitor,
+        )
+    }
+
+    fn with_window_properties(
+        config: InputConfig,
+        window_size: PhysicalSize<u32>,
+        scale_factor: f64,
+        monitors: Vec<MonitorInfo>,
+        current_monitor: Option<MonitorInfo>,
+    ) -> InputState {
         InputState {
-            window_size: window.inner_size(),
-            scale_factor: window.scale_factor(),
+            config,
+            window_size,
+            scale_factor,
+            monitors,
+            current_monitor,
             key_is_down: [false; Self::KEYBOARD_BUTTON_COUNT],
             key_just_down: [false; Self::KEYBOARD_BUTTON_COUNT],
             key_just_up: [false; Self::KEYBOARD_BUTTON_COUNT],
             mouse_position: PhysicalPosition::new(0.0, 0.0),
             mouse_wheel_delta: MouseScrollDelta::LineDelta(0.0, 0.0),
+            mouse_motion: (0.0, 0.0),
             mouse_button_is_down: [false; Self::MOUSE_BUTTON_COUNT],
             mouse_button_just_down: [None; Self::MOUSE_BUTTON_COUNT],
             mouse_button_just_up: [None; Self::MOUSE_BUTTON_COUNT],
             mouse_button_just_clicked: [None; Self::MOUSE_BUTTON_COUNT],
             mouse_button_went_down_position: [None; Self::MOUSE_BUTTON_COUNT],
             mouse_button_went_up_position: [None; Self::MOUSE_BUTTON_COUNT],
+            mouse_button_went_down_time: [None; Self::MOUSE_BUTTON_COUNT],
+            mouse_button_click_count: [0; Self::MOUSE_BUTTON_COUNT],
+            mouse_button_last_click: [None; Self::MOUSE_BUTTON_COUNT],
+            mouse_long_press_in_progress: [false; Self::MOUSE_BUTTON_COUNT],
+            mouse_long_press_just_started: [None; Self::MOUSE_BUTTON_COUNT],
             mouse_drag_in_progress: [None; Self::MOUSE_BUTTON_COUNT],
             mouse_drag_just_finished: [None; Self::MOUSE_BUTTON_COUNT],
+            pen_samples: Vec::default(),
+            latest_pen_sample: None,
+            events: Vec::default(),
         }
     }
 
@@ -96,6 +300,19 @@ impl InputState {
     // Accessors
     //
 
+    /// Thresholds used for detecting drags, multi-clicks, and long presses
+    pub fn config(&self) -> &InputConfig {
+        &self.config
+    }
+
+    /// Change the thresholds used for detecting drags, multi-clicks, and long presses
+    pub fn set_config(
+        &mut self,
+        config: InputConfig,
+    ) {
+        self.config = config;
+    }
+
     /// Current size of window
     pub fn window_size(&self) -> PhysicalSize<u32> {
         self.window_size
@@ -106,6 +323,22 @@ impl InputState {
         self.scale_factor
     }
 
+    /// All monitors connected to the system. This is refreshed when the window moves, changes
+    /// scale factor, or gains focus.
+    pub fn monitors(&self) -> &[MonitorInfo] {
+        &self.monitors
+    }
+
+    /// The monitor the window is on, if known
+    pub fn current_monitor(&self) -> Option<&MonitorInfo> {
+        self.current_monitor.as_ref()
+    }
+
+    /// The monitor the OS considers primary, if known
+    pub fn primary_monitor(&self) -> Option<&MonitorInfo> {
+        self.monitors.iter().find(|monitor| monitor.is_primary)
+    }
+
     /// Returns true if the given key is down
     pub fn is_key_down(
         &self,
@@ -147,11 +380,37 @@ impl InputState {
         self.mouse_position
     }
 
+    /// Get the current mouse position in logical coordinates
+    pub fn mouse_position_logical(&self) -> LogicalPosition<f64> {
+        self.mouse_position.to_logical(self.scale_factor)
+    }
+
+    /// Get the current mouse position in canvas coordinates. `canvas_matrix` maps canvas
+    /// coordinates to the surface, for example as returned by
+    /// `CoordinateSystemHelper::coordinate_system_matrix`. Returns None if the matrix can't be
+    /// inverted.
+    pub fn mouse_position_in_canvas(
+        &self,
+        canvas_matrix: &skia_safe::Matrix,
+    ) -> Option<skia_safe::Point> {
+        let inverse = canvas_matrix.invert()?;
+        Some(inverse.map_xy(self.mouse_position.x as f32, self.mouse_position.y as f32))
+    }
+
     /// Get the scroll delta from the current frame
     pub fn mouse_wheel_delta(&self) -> MouseScrollDelta {
         self.mouse_wheel_delta
     }
 
+    /// The raw mouse motion accumulated during this frame. Unlike `mouse_position`, this keeps
+    /// changing while the cursor is grabbed, so it is suitable for camera control. Units are
+    /// device-specific and unaffected by cursor acceleration.
+    ///
+    /// On some platforms this is reported even when the window does not have focus.
+    pub fn mouse_motion(&self) -> (f64, f64) {
+        self.mouse_motion
+    }
+
     /// Returns true if the given button is down
     pub fn is_mouse_down(
         &self,
@@ -239,6 +498,78 @@ impl InputState {
         }
     }
 
+    /// Returns the number of clicks in the multi-click sequence if the button was just clicked,
+    /// otherwise 0. For example, this returns 2 for the second click of a double-click. Clicks are
+    /// part of the same sequence if they happen within `InputConfig::multi_click_interval` and
+    /// `InputConfig::multi_click_radius` of each other.
+    pub fn mouse_button_just_clicked_count(
+        &self,
+        mouse_button: MouseButton,
+    ) -> u32 {
+        if let Some(index) = Self::mouse_button_to_index(mouse_button) {
+            if self.mouse_button_just_clicked[index].is_some() {
+                return self.mouse_button_click_count[index];
+            }
+        }
+
+        0
+    }
+
+    /// Returns true if the button was just double-clicked
+    pub fn is_mouse_button_just_double_clicked(
+        &self,
+        mouse_button: MouseButton,
+    ) -> bool {
+        self.mouse_button_just_clicked_count(mouse_button) == 2
+    }
+
+    /// Returns true if the button was just triple-clicked
+    pub fn is_mouse_button_just_triple_clicked(
+        &self,
+        mouse_button: MouseButton,
+    ) -> bool {
+        self.mouse_button_just_clicked_count(mouse_button) == 3
+    }
+
+    /// Returns true if the button has been held down without dragging for at least
+    /// `InputConfig::long_press_duration`. A long press does not turn into a drag, and releasing
+    /// it does not count as a click.
+    pub fn is_mouse_long_press_in_progress(
+        &self,
+        mouse_button: MouseButton,
+    ) -> bool {
+        if let Some(index) = Self::mouse_button_to_index(mouse_button) {
+            self.mouse_long_press_in_progress[index]
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if the button became a long press during this frame
+    pub fn is_mouse_long_press_just_started(
+        &self,
+        mouse_button: MouseButton,
+    ) -> bool {
+        if let Some(index) = Self::mouse_button_to_index(mouse_button) {
+            self.mouse_long_press_just_started[index].is_some()
+        } else {
+            false
+        }
+    }
+
+    /// Returns the position of the button if it became a long press during this frame, otherwise
+    /// None
+    pub fn mouse_long_press_just_started_position(
+        &self,
+        mouse_button: MouseButton,
+    ) -> Option<PhysicalPosition<f64>> {
+        if let Some(index) = Self::mouse_button_to_index(mouse_button) {
+            self.mouse_long_press_just_started[index]
+        } else {
+            None
+        }
+    }
+
     /// Returns the position the button went down at previously. This could have been some time ago.
     pub fn mouse_button_went_down_position(
         &self,
@@ -288,6 +619,17 @@ impl InputState {
         }
     }
 
+    /// Returns the mouse drag state in canvas coordinates if a drag is in process, otherwise None.
+    /// See `MouseDragState::to_canvas`
+    pub fn mouse_drag_in_progress_in_canvas(
+        &self,
+        mouse_button: MouseButton,
+        canvas_matrix: &skia_safe::Matrix,
+    ) -> Option<CanvasMouseDragState> {
+        self.mouse_drag_in_progress(mouse_button)
+            .and_then(|drag| drag.to_canvas(canvas_matrix))
+    }
+
     /// Return true if a mouse drag completed in the previous frame, otherwise false
     pub fn is_mouse_drag_just_finished(
         &self,
@@ -312,6 +654,43 @@ impl InputState {
         }
     }
 
+    /// Returns every pen/mouse motion sample received during this frame, in the order they
+    /// arrived. Use this rather than `mouse_position` to draw smooth strokes.
+    pub fn pen_samples(&self) -> &[PenSample] {
+        &self.pen_samples
+    }
+
+    /// Returns the most recent pen/mouse motion sample. This could have been some time ago.
+    pub fn latest_pen_sample(&self) -> Option<PenSample> {
+        self.latest_pen_sample
+    }
+
+    /// Returns the most recently reported pen pressure, or None if the device does not report it
+    pub fn pen_pressure(&self) -> Option<f64> {
+        self.latest_pen_sample.and_then(|sample| sample.pressure)
+    }
+
+    /// Returns the most recently reported pen tilt, or None if the device does not report it
+    pub fn pen_tilt(&self) -> Option<f64> {
+        self.latest_pen_sample.and_then(|sample| sample.tilt)
+    }
+
+    /// Returns every input event received during this frame, in the order they arrived
+    pub fn events(&self) -> &[TimedInputEvent] {
+        &self.events
+    }
+
+    /// Returns information about a mouse drag in canvas coordinates if it just completed,
+    /// otherwise None. See `MouseDragState::to_canvas`
+    pub fn mouse_drag_just_finished_in_canvas(
+        &self,
+        mouse_button: MouseButton,
+        canvas_matrix: &skia_safe::Matrix,
+    ) -> Option<CanvasMouseDragState> {
+        self.mouse_drag_just_finished(mouse_button)
+            .and_then(|drag| drag.to_canvas(canvas_matrix))
+    }
+
     //
     // Handlers for significant events
     //
@@ -319,6 +698,7 @@ impl InputState {
     /// Call at the end of every frame. This clears events that were "just" completed.
     pub fn end_frame(&mut self) {
         self.mouse_wheel_delta = MouseScrollDelta::LineDelta(0.0, 0.0);
+        self.mouse_motion = (0.0, 0.0);
 
         for value in self.key_just_down.iter_mut() {
             *value = false;
@@ -344,11 +724,74 @@ impl InputState {
             *value = None;
         }
 
+        for value in self.mouse_long_press_just_started.iter_mut() {
+            *value = None;
+        }
+
         for value in self.mouse_drag_in_progress.iter_mut() {
             if let Some(v) = value {
                 v.previous_frame_delta = PhysicalPosition::new(0.0, 0.0);
             }
         }
+
+        self.pen_samples.clear();
+        self.events.clear();
+    }
+
+    /// Appends an event to the list of events received during this frame
+    fn push_event(
+        &mut self,
+        event: InputEvent,
+        position: PhysicalPosition<f64>,
+    ) {
+        self.events.push(TimedInputEvent {
+            event,
+            position,
+            time: std::time::Instant::now(),
+        });
+    }
+
+    /// Call when a pen sample is received. winit only reports pressure and tilt for touch events,
+    /// so platform-specific tablet integrations can call this to provide samples directly.
+    pub fn handle_pen_sample(
+        &mut self,
+        sample: PenSample,
+    ) {
+        self.pen_samples.push(sample);
+        self.latest_pen_sample = Some(sample);
+    }
+
+    /// Call when the window moves. This is sent continuously while the window is dragged, so only
+    /// the monitor the window is on is checked, and the monitors are refreshed if it changed.
+    /// Returns true if the window is now on a different monitor.
+    pub fn refresh_current_monitor(
+        &mut self,
+        window: &Window,
+    ) -> bool {
+        let current_monitor = monitor::current_monitor(window);
+        let cached_monitor = self
+            .current_monitor
+            .as_ref()
+            .map(|monitor| monitor.handle());
+        if current_monitor.as_ref() == cached_monitor {
+            return false;
+        }
+
+        self.refresh_monitors(window)
+    }
+
+    /// Call to refresh the list of monitors, i.e. when monitors may have been connected. Returns
+    /// true if the window is now on a different monitor, or a property of its monitor changed.
+    pub fn refresh_monitors(
+        &mut self,
+        window: &Window,
+    ) -> bool {
+        let (monitors, current_monitor) = monitor::enumerate_monitors(window);
+        let monitor_changed = current_monitor != self.current_monitor;
+
+        self.monitors = monitors;
+        self.current_monitor = current_monitor;
+        monitor_changed
     }
 
     /// Call when DPI factor changes
@@ -373,6 +816,18 @@ impl InputState {
         keyboard_button: VirtualKeyCode,
         button_state: ElementState,
     ) {
+        let event = if button_state == ElementState::Pressed {
+            InputEvent::KeyDown {
+                key: keyboard_button,
+                is_repeat: self.is_key_down(keyboard_button),
+            }
+        } else {
+            InputEvent::KeyUp {
+                key: keyboard_button,
+            }
+        };
+        self.push_event(event, self.mouse_position);
+
         if let Some(kc) = Self::keyboard_button_to_index(keyboard_button) {
             // Assign true if key is down, or false if key is up
             if button_state == ElementState::Pressed {
@@ -395,6 +850,12 @@ impl InputState {
         button: MouseButton,
         button_event: ElementState,
     ) {
+        let event = match button_event {
+            ElementState::Pressed => InputEvent::MouseButtonDown(button),
+            ElementState::Released => InputEvent::MouseButtonUp(button),
+        };
+        self.push_event(event, self.mouse_position);
+
         if let Some(button_index) = Self::mouse_button_to_index(button) {
             assert!(button_index < InputState::MOUSE_BUTTON_COUNT);
 
@@ -405,6 +866,8 @@ impl InputState {
                     self.mouse_button_is_down[button_index] = true;
 
                     self.mouse_button_went_down_position[button_index] = Some(self.mouse_position);
+                    self.mouse_button_went_down_time[button_index] =
+                        Some(std::time::Instant::now());
                 }
                 ElementState::Released => {
                     self.mouse_button_just_up[button_index] = Some(self.mouse_position);
@@ -432,11 +895,65 @@ impl InputState {
                             });
                         }
                         None => {
-                            self.mouse_button_just_clicked[button_index] = Some(self.mouse_position)
+                            if !self.mouse_long_press_in_progress[button_index] {
+                                self.handle_mouse_click(button_index);
+                            }
                         }
                     }
 
                     self.mouse_drag_in_progress[button_index] = None;
+                    self.mouse_long_press_in_progress[button_index] = false;
+                    self.mouse_button_went_down_time[button_index] = None;
+                }
+            }
+        }
+    }
+
+    /// Call when a button goes down and back up without dragging
+    fn handle_mouse_click(
+        &mut self,
+        button_index: usize,
+    ) {
+        let now = std::time::Instant::now();
+        let position = self.mouse_position;
+
+        // Determine if this click continues a multi-click sequence
+        let continues_sequence = match self.mouse_button_last_click[button_index] {
+            Some((last_position, last_time)) => {
+                now - last_time <= self.config.multi_click_interval
+                    && Self::distance_physical(last_position, position) / self.scale_factor
+                        <= self.config.multi_click_radius
+            }
+            None => false,
+        };
+
+        if continues_sequence {
+            self.mouse_button_click_count[button_index] += 1;
+        } else {
+            self.mouse_button_click_count[button_index] = 1;
+        }
+
+        self.mouse_button_last_click[button_index] = Some((position, now));
+        self.mouse_button_just_clicked[button_index] = Some(position);
+    }
+
+    /// Checks if any buttons have been held long enough to be considered a long press
+    fn update_long_presses(
+        &mut self,
+        now: std::time::Instant,
+    ) {
+        for i in 0..Self::MOUSE_BUTTON_COUNT {
+            if !self.mouse_button_is_down[i]
+                || self.mouse_long_press_in_progress[i]
+                || self.mouse_drag_in_progress[i].is_some()
+            {
+                continue;
+            }
+
+            if let Some(went_down_time) = self.mouse_button_went_down_time[i] {
+                if now - went_down_time >= self.config.long_press_duration {
+                    self.mouse_long_press_in_progress[i] = true;
+                    self.mouse_long_press_just_started[i] = Some(self.mouse_position);
                 }
             }
         }
@@ -451,6 +968,15 @@ impl InputState {
 
         // Update mouse position
         self.mouse_position = position;
+        self.push_event(InputEvent::MouseMoved, position);
+
+        self.handle_pen_sample(PenSample {
+            position,
+            pressure: None,
+            tilt: None,
+            in_contact: self.mouse_button_is_down[0],
+            time: std::time::Instant::now(),
+        });
 
         // Update drag in progress state
         for i in 0..Self::MOUSE_BUTTON_COUNT {
@@ -462,8 +988,14 @@ impl InputState {
                                 let min_drag_distance_met = Self::distance_physical(
                                     went_down_position,
                                     self.mouse_position,
-                                ) > Self::MIN_DRAG_DISTANCE;
-                                if min_drag_distance_met {
+                                ) / self.scale_factor
+                                    > self.config.drag_distance;
+
+                                // A long press can't turn into a drag
+                                if min_drag_distance_met && !self.mouse_long_press_in_progress[i] {
+                                    // A drag breaks any multi-click sequence
+                                    self.mouse_button_last_click[i] = None;
+
                                     // We dragged a non-trivial amount, start the drag
                                     Some(MouseDragState {
                                         begin_position: went_down_position,
@@ -512,10 +1044,47 @@ impl InputState {
         }
     }
 
+    /// Call when a touch event occurs. Pens on some platforms are reported as touches
+    fn handle_touch_event(
+        &mut self,
+        touch: &winit::event::Touch,
+    ) {
+        use crate::winit::event::Force;
+
+        self.push_event(
+            InputEvent::Touch {
+                id: touch.id,
+                phase: touch.phase,
+            },
+            touch.location,
+        );
+
+        let pressure = touch.force.map(|force| force.normalized());
+        let tilt = match touch.force {
+            Some(Force::Calibrated { altitude_angle, .. }) => altitude_angle,
+            _ => None,
+        };
+
+        let in_contact = match touch.phase {
+            TouchPhase::Started | TouchPhase::Moved => true,
+            TouchPhase::Ended | TouchPhase::Cancelled => false,
+        };
+
+        self.handle_pen_sample(PenSample {
+            position: touch.location,
+            pressure,
+            tilt,
+            in_contact,
+            time: std::time::Instant::now(),
+        });
+    }
+
     fn handle_mouse_wheel_event(
         &mut self,
         delta: MouseScrollDelta,
     ) {
+        self.push_event(InputEvent::MouseWheel(delta), self.mouse_position);
+
         // Try to add the delta to self.mouse_wheel_delta
         if let MouseScrollDelta::LineDelta(x1, y1) = self.mouse_wheel_delta {
             if let MouseScrollDelta::LineDelta(x2, y2) = delta {
@@ -551,25 +1120,27 @@ impl InputState {
         self.mouse_wheel_delta = delta;
     }
 
+    fn handle_mouse_motion_event(
+        &mut self,
+        delta: (f64, f64),
+    ) {
+        self.push_event(InputEvent::MouseMotion { delta }, self.mouse_position);
+
+        self.mouse_motion.0 += delta.0;
+        self.mouse_motion.1 += delta.1;
+    }
+
     /// Call when winit sends an event
     pub fn handle_winit_event<T>(
         &mut self,
-        app_control: &mut AppControl,
         event: &winit::event::Event<T>,
         _window_target: &winit::event_loop::EventLoopWindowTarget<T>,
     ) {
         use crate::winit::event::Event;
         use crate::winit::event::WindowEvent;
-
-        let mut is_close_requested = false;
+        use crate::winit::event::DeviceEvent;
 
         match event {
-            // Close if the window is killed
-            Event::WindowEvent {
-                event: WindowEvent::CloseRequested,
-                ..
-            } => is_close_requested = true,
-
             Event::WindowEvent {
                 event:
                     WindowEvent::ScaleFactorChanged {
@@ -599,6 +1170,14 @@ impl InputState {
                 }
             }
 
+            Event::WindowEvent {
+                event: WindowEvent::ReceivedCharacter(c),
+                ..
+            } => {
+                trace!("received character {:?}", c);
+                self.push_event(InputEvent::ReceivedCharacter(*c), self.mouse_position);
+            }
+
             Event::WindowEvent {
                 event:
                     WindowEvent::MouseInput {
@@ -643,14 +1222,26 @@ impl InputState {
                 self.handle_mouse_wheel_event(*delta);
             }
 
+            Event::WindowEvent {
+                event: WindowEvent::Touch(touch),
+                ..
+            } => {
+                trace!("touch {:?}", touch);
+                self.handle_touch_event(touch);
+            }
+
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => self.handle_mouse_motion_event(*delta),
+
+            // Long presses are detected by time passing rather than an input event, so check once all
+            // other events for this frame have been processed
+            Event::MainEventsCleared => self.update_long_presses(std::time::Instant::now()),
+
             // Ignore any other events
             _ => (),
         }
-
-        if is_close_requested {
-            trace!("close requested");
-            app_control.enqueue_terminate_process();
-        }
     }
 
     //
@@ -699,7 +1290,7 @@ impl InputState {
         }
     }
 
-    /// Adds two logical positions (p0 + p1)
+    /// Adds two physical positions (p0 + p1)
     fn add_physical(
         p0: PhysicalPosition<f64>,
         p1: PhysicalPosition<f64>,
@@ -707,7 +1298,7 @@ impl InputState {
         PhysicalPosition::new(p0.x + p1.x, p0.y + p1.y)
     }
 
-    /// Subtracts two logical positions (p0 - p1)
+    /// Subtracts two physical positions (p0 - p1)
     fn subtract_physical(
         p0: PhysicalPosition<f64>,
         p1: PhysicalPosition<f64>,
@@ -715,7 +1306,7 @@ impl InputState {
         PhysicalPosition::new(p0.x - p1.x, p0.y - p1.y)
     }
 
-    /// Gets the distance between two logical positions
+    /// Gets the distance between two physical positions
     fn distance_physical(
         p0: PhysicalPosition<f64>,
         p1: PhysicalPosition<f64>,
@@ -726,3 +1317,317 @@ impl InputState {
         ((x_diff * x_diff) + (y_diff * y_diff)).sqrt()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::winit::event::DeviceId;
+    use crate::winit::event::Force;
+    use crate::winit::event::Touch;
+
+    fn input_state() -> InputState {
+        InputState::with_window_properties(
+            InputConfig::default(),
+            PhysicalSize::new(800, 600),
+            1.0,
+            Vec::new(),
+            None,
+        )
+    }
+
+    fn input_state_with_config(
+        config: InputConfig,
+        scale_factor: f64,
+    ) -> InputState {
+        InputState::with_window_properties(
+            config,
+            PhysicalSize::new(800, 600),
+            scale_factor,
+            Vec::new(),
+            None,
+        )
+    }
+
+    fn click(
+        input_state: &mut InputState,
+        x: f64,
+        y: f64,
+    ) {
+        input_state.end_frame();
+        input_state.handle_mouse_move_event(PhysicalPosition::new(x, y));
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Pressed);
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Released);
+    }
+
+    fn touch(
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+        force: Option<Force>,
+    ) -> Touch {
+        Touch {
+            device_id: unsafe { DeviceId::dummy() },
+            phase,
+            location: PhysicalPosition::new(x, y),
+            force,
+            id: 0,
+        }
+    }
+
+    #[test]
+    fn mouse_motion_keeps_every_sample() {
+        let mut input_state = input_state();
+        for i in 1..=3 {
+            input_state.handle_mouse_move_event(PhysicalPosition::new(i as f64, i as f64 * 2.0));
+        }
+
+        let positions: Vec<_> = input_state
+            .pen_samples()
+            .iter()
+            .map(|sample| (sample.position.x, sample.position.y))
+            .collect();
+        assert_eq!(positions, vec![(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)]);
+        assert!(input_state
+            .pen_samples()
+            .iter()
+            .all(|sample| sample.pressure.is_none() && !sample.in_contact));
+        assert_eq!(
+            input_state.mouse_position(),
+            PhysicalPosition::new(3.0, 6.0)
+        );
+
+        // Samples are only kept for one frame, but the latest one is remembered
+        input_state.end_frame();
+        assert!(input_state.pen_samples().is_empty());
+        assert_eq!(
+            input_state.latest_pen_sample().unwrap().position,
+            PhysicalPosition::new(3.0, 6.0)
+        );
+    }
+
+    #[test]
+    fn mouse_samples_are_in_contact_while_left_button_is_down() {
+        let mut input_state = input_state();
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Pressed);
+        input_state.handle_mouse_move_event(PhysicalPosition::new(10.0, 10.0));
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Released);
+        input_state.handle_mouse_move_event(PhysicalPosition::new(20.0, 10.0));
+
+        let in_contact: Vec<_> = input_state
+            .pen_samples()
+            .iter()
+            .map(|sample| sample.in_contact)
+            .collect();
+        assert_eq!(in_contact, vec![true, false]);
+    }
+
+    #[test]
+    fn touch_reports_pressure_and_tilt() {
+        let mut input_state = input_state();
+        let calibrated = Force::Calibrated {
+            force: 2.0,
+            max_possible_force: 4.0,
+            altitude_angle: Some(std::f64::consts::FRAC_PI_2),
+        };
+
+        input_state.handle_touch_event(&touch(TouchPhase::Started, 1.0, 1.0, Some(calibrated)));
+        input_state.handle_touch_event(&touch(
+            TouchPhase::Moved,
+            2.0,
+            2.0,
+            Some(Force::Normalized(0.25)),
+        ));
+        input_state.handle_touch_event(&touch(TouchPhase::Ended, 3.0, 3.0, None));
+
+        let samples = input_state.pen_samples();
+        assert_eq!(samples.len(), 3);
+
+        assert!((samples[0].pressure.unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(samples[0].tilt, Some(std::f64::consts::FRAC_PI_2));
+        assert!(samples[0].in_contact);
+
+        assert_eq!(samples[1].pressure, Some(0.25));
+        assert_eq!(samples[1].tilt, None);
+        assert!(samples[1].in_contact);
+
+        assert_eq!(samples[2].pressure, None);
+        assert!(!samples[2].in_contact);
+        assert_eq!(samples[2].position, PhysicalPosition::new(3.0, 3.0));
+
+        assert_eq!(input_state.pen_pressure(), None);
+        assert_eq!(input_state.events().len(), 3);
+    }
+
+    #[test]
+    fn handled_pen_samples_are_recorded() {
+        let mut input_state = input_state();
+        let sample = PenSample {
+            position: PhysicalPosition::new(5.0, 5.0),
+            pressure: Some(0.75),
+            tilt: Some(1.0),
+            in_contact: true,
+            time: std::time::Instant::now(),
+        };
+
+        input_state.handle_pen_sample(sample);
+        assert_eq!(input_state.pen_samples().len(), 1);
+        assert_eq!(input_state.pen_pressure(), Some(0.75));
+        assert_eq!(input_state.pen_tilt(), Some(1.0));
+    }
+
+    #[test]
+    fn clicks_within_interval_and_radius_are_counted() {
+        let mut input_state = input_state();
+
+        click(&mut input_state, 10.0, 10.0);
+        assert_eq!(
+            input_state.mouse_button_just_clicked_count(MouseButton::Left),
+            1
+        );
+
+        click(&mut input_state, 12.0, 11.0);
+        assert_eq!(
+            input_state.mouse_button_just_clicked_count(MouseButton::Left),
+            2
+        );
+        assert!(input_state.is_mouse_button_just_double_clicked(MouseButton::Left));
+
+        click(&mut input_state, 10.0, 10.0);
+        assert!(input_state.is_mouse_button_just_triple_clicked(MouseButton::Left));
+
+        // The count is only reported in the frame the click happened
+        input_state.end_frame();
+        assert_eq!(
+            input_state.mouse_button_just_clicked_count(MouseButton::Left),
+            0
+        );
+    }
+
+    #[test]
+    fn multi_click_radius_is_in_logical_units() {
+        let config = InputConfig::default();
+
+        // 6 physical pixels apart is 6 logical units at 1x, outside the 4 unit radius
+        let mut input_state = input_state_with_config(config, 1.0);
+        click(&mut input_state, 10.0, 10.0);
+        click(&mut input_state, 16.0, 10.0);
+        assert_eq!(
+            input_state.mouse_button_just_clicked_count(MouseButton::Left),
+            1
+        );
+
+        // At 2x it is 3 logical units, so the clicks are a double-click
+        let mut input_state = input_state_with_config(config, 2.0);
+        click(&mut input_state, 10.0, 10.0);
+        click(&mut input_state, 16.0, 10.0);
+        assert_eq!(
+            input_state.mouse_button_just_clicked_count(MouseButton::Left),
+            2
+        );
+    }
+
+    #[test]
+    fn clicks_after_interval_start_a_new_sequence() {
+        let config = InputConfig {
+            multi_click_interval: std::time::Duration::from_millis(0),
+            ..Default::default()
+        };
+        let mut input_state = input_state_with_config(config, 1.0);
+
+        click(&mut input_state, 10.0, 10.0);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        click(&mut input_state, 10.0, 10.0);
+        assert_eq!(
+            input_state.mouse_button_just_clicked_count(MouseButton::Left),
+            1
+        );
+    }
+
+    #[test]
+    fn drag_starts_past_drag_distance() {
+        // 2 logical units at 2x is 4 physical pixels
+        let mut input_state = input_state_with_config(InputConfig::default(), 2.0);
+        input_state.handle_mouse_move_event(PhysicalPosition::new(0.0, 0.0));
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Pressed);
+
+        input_state.handle_mouse_move_event(PhysicalPosition::new(3.0, 0.0));
+        assert!(!input_state.is_mouse_drag_in_progress(MouseButton::Left));
+
+        input_state.handle_mouse_move_event(PhysicalPosition::new(5.0, 0.0));
+        let drag = input_state
+            .mouse_drag_in_progress(MouseButton::Left)
+            .unwrap();
+        assert_eq!(drag.begin_position, PhysicalPosition::new(0.0, 0.0));
+        assert_eq!(
+            drag.accumulated_frame_delta,
+            PhysicalPosition::new(5.0, 0.0)
+        );
+
+        input_state.handle_mouse_move_event(PhysicalPosition::new(8.0, 1.0));
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Released);
+
+        // A drag is not a click
+        assert!(!input_state.is_mouse_button_just_clicked(MouseButton::Left));
+        let drag = input_state
+            .mouse_drag_just_finished(MouseButton::Left)
+            .unwrap();
+        assert_eq!(drag.end_position, PhysicalPosition::new(8.0, 1.0));
+        assert_eq!(
+            drag.accumulated_frame_delta,
+            PhysicalPosition::new(8.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn small_movement_is_still_a_click() {
+        let mut input_state = input_state();
+        input_state.handle_mouse_move_event(PhysicalPosition::new(0.0, 0.0));
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Pressed);
+        input_state.handle_mouse_move_event(PhysicalPosition::new(1.0, 1.0));
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Released);
+
+        assert!(!input_state.is_mouse_drag_just_finished(MouseButton::Left));
+        assert_eq!(
+            input_state.mouse_button_just_clicked_position(MouseButton::Left),
+            Some(PhysicalPosition::new(1.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn long_press_starts_after_duration() {
+        let config = InputConfig::default();
+        let mut input_state = input_state_with_config(config, 1.0);
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Pressed);
+        let pressed_time = std::time::Instant::now();
+
+        input_state.update_long_presses(pressed_time);
+        assert!(!input_state.is_mouse_long_press_in_progress(MouseButton::Left));
+
+        input_state.update_long_presses(pressed_time + config.long_press_duration);
+        assert!(input_state.is_mouse_long_press_in_progress(MouseButton::Left));
+        assert!(input_state.is_mouse_long_press_just_started(MouseButton::Left));
+
+        input_state.end_frame();
+        assert!(input_state.is_mouse_long_press_in_progress(MouseButton::Left));
+        assert!(!input_state.is_mouse_long_press_just_started(MouseButton::Left));
+
+        // A long press does not turn into a drag or a click
+        input_state.handle_mouse_move_event(PhysicalPosition::new(50.0, 50.0));
+        assert!(!input_state.is_mouse_drag_in_progress(MouseButton::Left));
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Released);
+        assert!(!input_state.is_mouse_button_just_clicked(MouseButton::Left));
+        assert!(!input_state.is_mouse_long_press_in_progress(MouseButton::Left));
+    }
+
+    #[test]
+    fn drag_prevents_long_press() {
+        let config = InputConfig::default();
+        let mut input_state = input_state_with_config(config, 1.0);
+        input_state.handle_mouse_button_event(MouseButton::Left, ElementState::Pressed);
+        input_state.handle_mouse_move_event(PhysicalPosition::new(50.0, 50.0));
+
+        input_state.update_long_presses(std::time::Instant::now() + config.long_press_duration);
+        assert!(!input_state.is_mouse_long_press_in_progress(MouseButton::Left));
+        assert!(input_state.is_mouse_drag_in_progress(MouseButton::Left));
+    }
+}
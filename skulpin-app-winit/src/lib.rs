@@ -9,18 +9,50 @@ pub use app::AppError;
 pub use app::AppUpdateArgs;
 pub use app::AppDrawArgs;
 
+mod app_runner;
+pub use app_runner::AppRunner;
+
+mod app_event;
+pub use app_event::AppEventSender;
+pub use app_event::SendEventError;
+
 mod app_control;
 pub use app_control::AppControl;
+pub use app_control::WindowCommand;
+pub use app_control::CursorGrabMode;
+pub use app_control::CursorIcon;
+pub use app_control::FullscreenMode;
+pub use app_control::Icon;
+
+mod monitor;
+pub use monitor::MonitorInfo;
+pub use monitor::VideoModeInfo;
+pub use monitor::MonitorSelection;
+
+mod clipboard;
+pub use clipboard::Clipboard;
+pub use clipboard::ClipboardBackend;
+pub use clipboard::ClipboardError;
+pub use clipboard::ClipboardImage;
+pub use clipboard::MemoryClipboard;
+#[cfg(feature = "clipboard")]
+pub use clipboard::SystemClipboard;
 
 mod input_state;
 pub use input_state::InputState;
+pub use input_state::InputConfig;
 pub use input_state::MouseDragState;
+pub use input_state::CanvasMouseDragState;
+pub use input_state::PenSample;
+pub use input_state::InputEvent;
+pub use input_state::TimedInputEvent;
 
 // These are re-exported winit types
 pub use input_state::VirtualKeyCode;
 pub use input_state::MouseButton;
 pub use input_state::MouseScrollDelta;
 pub use input_state::ElementState;
+pub use input_state::TouchPhase;
 pub use input_state::LogicalSize;
 pub use input_state::PhysicalSize;
 pub use input_state::LogicalPosition;
@@ -28,10 +60,25 @@ pub use input_state::PhysicalPosition;
 pub use input_state::Position;
 pub use input_state::Size;
 
+mod scene;
+pub use scene::Scene;
+pub use scene::SceneControl;
+pub use scene::SceneStack;
+pub use scene::SceneTransition;
+
+mod task;
+pub use task::TaskExecutor;
+pub use task::TaskHandle;
+pub use task::ThreadTask;
+pub use task::run_on_thread;
+
 mod time_state;
 pub use time_state::TimeState;
 pub use time_state::TimeContext;
 
+mod window_state;
+pub use window_state::WindowState;
+
 mod util;
 pub use util::PeriodicEvent;
 pub use util::ScopeTimer;
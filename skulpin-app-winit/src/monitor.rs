@@ -0,0 +1,212 @@
+//! Information about the monitors connected to the system. The monitors are listed by
+//! `InputState::monitors`, and `AppBuilder::monitor` chooses which monitor the window opens on.
+
+use crate::winit;
+
+use winit::dpi::PhysicalPosition;
+use winit::dpi::PhysicalSize;
+use winit::event_loop::EventLoop;
+use winit::monitor::MonitorHandle;
+use winit::monitor::VideoMode;
+use winit::window::Window;
+
+/// A fullscreen video mode supported by a monitor
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VideoModeInfo {
+    /// Resolution of the video mode in pixels
+    pub size: PhysicalSize<u32>,
+
+    /// Bits per pixel
+    pub bit_depth: u16,
+
+    /// Refresh rate in hertz
+    pub refresh_rate: u16,
+}
+
+impl VideoModeInfo {
+    fn new(video_mode: &VideoMode) -> Self {
+        VideoModeInfo {
+            size: video_mode.size(),
+            bit_depth: video_mode.bit_depth(),
+            refresh_rate: video_mode.refresh_rate(),
+        }
+    }
+}
+
+/// A snapshot of a monitor's properties
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorInfo {
+    /// Human-readable name of the monitor, if the platform provides one
+    pub name: Option<String>,
+
+    /// The top-left of the monitor in the desktop's coordinate space
+    pub position: PhysicalPosition<i32>,
+
+    /// Resolution of the monitor in pixels
+    pub size: PhysicalSize<u32>,
+
+    /// The scaling factor due to high-dpi screens
+    pub scale_factor: f64,
+
+    /// True if the OS considers this the primary monitor
+    pub is_primary: bool,
+
+    handle: MonitorHandle,
+}
+
+impl MonitorInfo {
+    pub(crate) fn new(
+        monitor: &MonitorHandle,
+        primary_monitor: Option<&MonitorHandle>,
+    ) -> Self {
+        MonitorInfo {
+            name: monitor.name(),
+            position: monitor.position(),
+            size: monitor.size(),
+            scale_factor: monitor.scale_factor(),
+            is_primary: primary_monitor == Some(monitor),
+            handle: monitor.clone(),
+        }
+    }
+
+    /// The winit handle for the monitor
+    pub fn handle(&self) -> &MonitorHandle {
+        &self.handle
+    }
+
+    /// Video modes that can be used for exclusive fullscreen. These are queried from the OS each
+    /// time this is called.
+    pub fn video_modes(&self) -> Vec<VideoModeInfo> {
+        self.handle
+            .video_modes()
+            .map(|video_mode| VideoModeInfo::new(&video_mode))
+            .collect()
+    }
+
+    /// The distinct refresh rates of the monitor's video modes, highest first
+    pub fn refresh_rates(&self) -> Vec<u16> {
+        let mut refresh_rates: Vec<_> = self
+            .video_modes()
+            .iter()
+            .map(|video_mode| video_mode.refresh_rate)
+            .collect();
+
+        refresh_rates.sort_unstable_by(|a, b| b.cmp(a));
+        refresh_rates.dedup();
+        refresh_rates
+    }
+}
+
+/// Determines which monitor the window opens on. See `AppBuilder::monitor`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MonitorSelection {
+    /// The monitor the OS considers primary
+    Primary,
+
+    /// The monitor at the given index of `InputState::monitors`
+    Index(usize),
+
+    /// The monitor with the given name
+    Name(String),
+}
+
+impl MonitorSelection {
+    /// Find the selected monitor among the given monitors. Returns None if it isn't connected.
+    pub(crate) fn find(
+        &self,
+        monitors: &[MonitorHandle],
+        primary_monitor: Option<&MonitorHandle>,
+    ) -> Option<MonitorHandle> {
+        match self {
+            MonitorSelection::Primary => primary_monitor.cloned(),
+            MonitorSelection::Index(index) => monitors.get(*index).cloned(),
+            MonitorSelection::Name(name) => monitors
+                .iter()
+                .find(|monitor| monitor.name().as_ref() == Some(name))
+                .cloned(),
+        }
+    }
+}
+
+/// Returns the monitor the window is on, if known
+pub(crate) fn current_monitor(window: &Window) -> Option<MonitorHandle> {
+    // Older versions of winit do not return an Option
+    #[cfg(any(feature = "winit-21", feature = "winit-22"))]
+    {
+        Some(window.current_monitor())
+    }
+
+    #[cfg(not(any(feature = "winit-21", feature = "winit-22")))]
+    {
+        window.current_monitor()
+    }
+}
+
+/// Returns the monitor's video mode that matches the given one. If no mode is given or the monitor
+/// doesn't support it, returns the largest mode with the highest refresh rate.
+pub(crate) fn find_video_mode(
+    monitor: &MonitorHandle,
+    video_mode: Option<VideoModeInfo>,
+) -> Option<VideoMode> {
+    if let Some(video_mode) = video_mode {
+        let found = monitor
+            .video_modes()
+            .find(|x| VideoModeInfo::new(x) == video_mode);
+
+        if found.is_some() {
+            return found;
+        }
+
+        warn!(
+            "Video mode {:?} is not supported by monitor {:?}, using its best mode instead",
+            video_mode,
+            monitor.name()
+        );
+    }
+
+    monitor.video_modes().max_by_key(|x| {
+        let size = x.size();
+        (
+            u64::from(size.width) * u64::from(size.height),
+            x.refresh_rate(),
+            x.bit_depth(),
+        )
+    })
+}
+
+/// Returns info for every available monitor, and for the monitor the window is on
+pub(crate) fn enumerate_monitors(window: &Window) -> (Vec<MonitorInfo>, Option<MonitorInfo>) {
+    // Older versions of winit do not return an Option
+    #[cfg(any(feature = "winit-21", feature = "winit-22"))]
+    let primary_monitor = Some(window.primary_monitor());
+
+    #[cfg(not(any(feature = "winit-21", feature = "winit-22")))]
+    let primary_monitor = window.primary_monitor();
+
+    let current_monitor = current_monitor(window);
+
+    let monitors = window
+        .available_monitors()
+        .map(|monitor| MonitorInfo::new(&monitor, primary_monitor.as_ref()))
+        .collect();
+
+    let current_monitor =
+        current_monitor.map(|monitor| MonitorInfo::new(&monitor, primary_monitor.as_ref()));
+
+    (monitors, current_monitor)
+}
+
+/// Returns every available monitor and the primary monitor before a window exists. winit 0.21 and
+/// 0.22 only provide these on the `EventLoop`, not on the `EventLoopWindowTarget`.
+pub(crate) fn event_loop_monitors<T>(
+    event_loop: &EventLoop<T>
+) -> (Vec<MonitorHandle>, Option<MonitorHandle>) {
+    // Older versions of winit do not return an Option
+    #[cfg(any(feature = "winit-21", feature = "winit-22"))]
+    let primary_monitor = Some(event_loop.primary_monitor());
+
+    #[cfg(not(any(feature = "winit-21", feature = "winit-22")))]
+    let primary_monitor = event_loop.primary_monitor();
+
+    (event_loop.available_monitors().collect(), primary_monitor)
+}
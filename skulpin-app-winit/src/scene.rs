@@ -0,0 +1,1081 @@
+//! An optional `AppHandler` that manages a stack of scenes (i.e. menu, game, pause screen). Scenes
+//! are pushed, popped, and replaced with the `SceneControl` passed to their callbacks, optionally
+//! with a transition animation.
+//!
+//! Scene changes are queued on `SceneControl` rather than `AppControl`. `AppControl` is shared by
+//! every `AppHandler` and is not generic over the app's user event type, so it can't hold the
+//! `Scene<U>` being pushed. `SceneControl` is passed next to `AppControl` in every scene callback.
+
+use crate::skia_safe;
+
+use super::app::AppHandler;
+use super::app::AppError;
+use super::app::AppUpdateArgs;
+use super::app::AppDrawArgs;
+use super::app_control::AppControl;
+use super::input_state::TimedInputEvent;
+use super::input_state::PhysicalSize;
+use super::monitor::MonitorInfo;
+
+use std::path::Path;
+use std::time::Duration;
+
+/// A single screen of an app, managed by a `SceneStack`. Only the top scene is updated and receives
+/// input. Use the `SceneControl` passed to the callbacks to change scenes.
+///
+/// `U` is the type of event sent to the app with an `AppEventSender`.
+pub trait Scene<U = ()> {
+    /// Called every frame while this is the top scene
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+        scene_control: &mut SceneControl<U>,
+    );
+
+    /// Called every frame while this scene is visible. Scenes below an overlay scene remain visible.
+    /// The canvas state (i.e. the matrix) is restored after each scene draws.
+    fn draw(
+        &mut self,
+        draw_args: AppDrawArgs,
+    );
+
+    /// Called for every input event received during the frame, before `update`, while this is the
+    /// top scene
+    fn input_event(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+        _event: &TimedInputEvent,
+    ) {
+    }
+
+    /// Called when this scene is added to the stack
+    fn on_enter(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when this scene is removed from the stack, including when the app shuts down
+    fn on_exit(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when another scene is pushed on top of this scene
+    fn on_pause(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when this becomes the top scene again because the scene above it was removed
+    fn on_resume(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// If true, the scenes below this one are drawn first (i.e. a pause menu drawn over the game)
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    /// Called when the user tries to close the window while this is the top scene. Return false to
+    /// keep the window open.
+    fn close_requested(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) -> bool {
+        true
+    }
+
+    /// Called when an event is sent to the app with an `AppEventSender` while this is the top
+    /// scene
+    fn user_event(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+        _event: &U,
+    ) {
+    }
+
+    /// Called after the renderer was recreated because the GPU device was lost. Every scene in the
+    /// stack is notified, and should create any GPU images it holds again.
+    fn renderer_recreated(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when the window is resized. Every scene in the stack is notified.
+    fn resized(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+        _inner_size: PhysicalSize<u32>,
+    ) {
+    }
+
+    /// Called when the window's scale factor changes. Every scene in the stack is notified.
+    fn scale_factor_changed(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+        _scale_factor: f64,
+    ) {
+    }
+
+    /// Called when the window moves to a different monitor, or the properties of its monitor
+    /// change. Every scene in the stack is notified.
+    fn monitor_changed(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+        _monitor: &MonitorInfo,
+    ) {
+    }
+
+    /// Called when the window gains keyboard focus. Every scene in the stack is notified.
+    fn focus_gained(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when the window loses keyboard focus. Every scene in the stack is notified.
+    fn focus_lost(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when the window is minimized. Every scene in the stack is notified.
+    fn minimized(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when the window is restored after being minimized. Every scene in the stack is
+    /// notified.
+    fn restored(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when a file is dragged over the window while this is the top scene
+    fn file_hovered(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+        _path: &Path,
+    ) {
+    }
+
+    /// Called when files that were dragged over the window leave it without being dropped, while
+    /// this is the top scene
+    fn file_hover_cancelled(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+    ) {
+    }
+
+    /// Called when a file is dropped on the window while this is the top scene
+    fn file_dropped(
+        &mut self,
+        _app_control: &mut AppControl,
+        _scene_control: &mut SceneControl<U>,
+        _path: &Path,
+    ) {
+    }
+
+    /// Called when the app encounters a fatal error. Every scene in the stack is notified.
+    fn fatal_error(
+        &mut self,
+        _error: &AppError,
+    ) {
+    }
+}
+
+/// Animation played when the top scene changes
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SceneTransition {
+    /// Switch scenes immediately
+    None,
+
+    /// Fade out to the given color and then fade in to the new scene
+    Fade {
+        color: skia_safe::Color,
+        duration: Duration,
+    },
+
+    /// Blend from the old scene to the new scene
+    Crossfade { duration: Duration },
+}
+
+impl Default for SceneTransition {
+    fn default() -> Self {
+        SceneTransition::None
+    }
+}
+
+impl SceneTransition {
+    fn duration(&self) -> Duration {
+        match self {
+            SceneTransition::None => Duration::from_secs(0),
+            SceneTransition::Fade { duration, .. } => *duration,
+            SceneTransition::Crossfade { duration } => *duration,
+        }
+    }
+}
+
+// A change to the scene stack, queued on `SceneControl`
+enum SceneCommand<U> {
+    Push(Box<dyn Scene<U>>, SceneTransition),
+    Pop(SceneTransition),
+    Replace(Box<dyn Scene<U>>, SceneTransition),
+}
+
+/// Passed to scene callbacks to change the scene stack. Changes are queued and applied by the
+/// `SceneStack` after the callback returns.
+pub struct SceneControl<U = ()> {
+    commands: Vec<SceneCommand<U>>,
+}
+
+// Derive would require U: Default
+impl<U> Default for SceneControl<U> {
+    fn default() -> Self {
+        SceneControl {
+            commands: Vec::default(),
+        }
+    }
+}
+
+impl<U: 'static> SceneControl<U> {
+    /// Push a scene on top of the scene stack. The current top scene is paused.
+    pub fn push_scene<S: Scene<U> + 'static>(
+        &mut self,
+        scene: S,
+        transition: SceneTransition,
+    ) {
+        self.commands
+            .push(SceneCommand::Push(Box::new(scene), transition));
+    }
+
+    /// Remove the top scene from the scene stack. The scene below it is resumed. If the last scene
+    /// is popped, the app terminates.
+    pub fn pop_scene(
+        &mut self,
+        transition: SceneTransition,
+    ) {
+        self.commands.push(SceneCommand::Pop(transition));
+    }
+
+    /// Replace the top scene of the scene stack
+    pub fn replace_scene<S: Scene<U> + 'static>(
+        &mut self,
+        scene: S,
+        transition: SceneTransition,
+    ) {
+        self.commands
+            .push(SceneCommand::Replace(Box::new(scene), transition));
+    }
+
+    /// Returns true if there are scene changes that have not been processed yet
+    pub fn has_pending_commands(&self) -> bool {
+        !self.commands.is_empty()
+    }
+
+    fn take_commands(&mut self) -> Vec<SceneCommand<U>> {
+        std::mem::take(&mut self.commands)
+    }
+}
+
+// A transition that is currently playing
+struct ActiveTransition<U> {
+    transition: SceneTransition,
+    elapsed: Duration,
+
+    // The number of scenes from the bottom of the stack that were below the removed scene (or the
+    // whole stack if nothing was removed) before the change
+    previous_stack_depth: usize,
+
+    // The scene that was removed, kept so that it can be drawn until the transition completes
+    removed_scene: Option<Box<dyn Scene<U>>>,
+}
+
+impl<U> ActiveTransition<U> {
+    fn progress(&self) -> f32 {
+        let duration = self.transition.duration().as_secs_f32();
+        if duration > 0.0 {
+            (self.elapsed.as_secs_f32() / duration).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// An `AppHandler` that manages a stack of scenes. Only the top scene is updated and receives
+/// input. Use `SceneControl::push_scene`, `SceneControl::pop_scene` and
+/// `SceneControl::replace_scene` to change scenes. The app terminates when the last scene is
+/// popped.
+pub struct SceneStack<U = ()> {
+    scenes: Vec<Box<dyn Scene<U>>>,
+
+    // Commands queued by the scenes (or the initial scene) that have not been processed yet
+    scene_control: SceneControl<U>,
+    active_transition: Option<ActiveTransition<U>>,
+}
+
+impl<U: 'static> SceneStack<U> {
+    /// Number of scene commands that will be processed in a row before giving up. This prevents an
+    /// infinite loop if scenes keep pushing scenes from `on_enter`
+    const MAX_COMMANDS_PER_FRAME: usize = 64;
+
+    /// Create a scene stack with the given scene as the first scene
+    pub fn new<S: Scene<U> + 'static>(initial_scene: S) -> Self {
+        let mut scene_control = SceneControl::default();
+        scene_control.push_scene(initial_scene, SceneTransition::None);
+
+        SceneStack {
+            scenes: Vec::default(),
+            scene_control,
+            active_transition: None,
+        }
+    }
+
+    /// Number of scenes in the stack
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Returns true if there are no scenes in the stack
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Returns true if a transition animation is playing
+    pub fn is_transitioning(&self) -> bool {
+        self.active_transition.is_some()
+    }
+
+    // Apply queued pushes/pops/replaces to the stack
+    fn process_commands(
+        &mut self,
+        app_control: &mut AppControl,
+    ) {
+        // Scene callbacks may queue more commands while the commands are processed
+        let mut scene_control = std::mem::take(&mut self.scene_control);
+
+        let mut processed_count = 0;
+        loop {
+            let commands = scene_control.take_commands();
+            if commands.is_empty() {
+                break;
+            }
+
+            if processed_count > Self::MAX_COMMANDS_PER_FRAME {
+                warn!(
+                    "More than {} scene commands processed in a single frame, deferring the rest",
+                    Self::MAX_COMMANDS_PER_FRAME
+                );
+                scene_control.commands = commands;
+                break;
+            }
+
+            for command in commands {
+                processed_count += 1;
+                self.process_command(app_control, &mut scene_control, command);
+            }
+        }
+
+        self.scene_control = scene_control;
+
+        if self.scenes.is_empty() {
+            debug!("Last scene was popped, terminating");
+            app_control.enqueue_terminate_process();
+        }
+    }
+
+    fn process_command(
+        &mut self,
+        app_control: &mut AppControl,
+        scene_control: &mut SceneControl<U>,
+        command: SceneCommand<U>,
+    ) {
+        let previous_stack_depth = self.scenes.len();
+
+        let (transition, removed_scene) = match command {
+            SceneCommand::Push(mut scene, transition) => {
+                if let Some(top) = self.scenes.last_mut() {
+                    top.on_pause(app_control, scene_control);
+                }
+
+                scene.on_enter(app_control, scene_control);
+                self.scenes.push(scene);
+                (transition, None)
+            }
+            SceneCommand::Pop(transition) => {
+                let removed_scene = self.scenes.pop().map(|mut scene| {
+                    scene.on_exit(app_control, scene_control);
+                    scene
+                });
+
+                if removed_scene.is_none() {
+                    warn!("Tried to pop a scene but the scene stack is empty");
+                }
+
+                if let Some(top) = self.scenes.last_mut() {
+                    top.on_resume(app_control, scene_control);
+                }
+
+                (transition, removed_scene)
+            }
+            SceneCommand::Replace(mut scene, transition) => {
+                let removed_scene = self.scenes.pop().map(|mut scene| {
+                    scene.on_exit(app_control, scene_control);
+                    scene
+                });
+
+                scene.on_enter(app_control, scene_control);
+                self.scenes.push(scene);
+                (transition, removed_scene)
+            }
+        };
+
+        // A new transition replaces any transition that is still playing
+        self.active_transition = if transition.duration() > Duration::from_secs(0) {
+            Some(ActiveTransition {
+                transition,
+                elapsed: Duration::from_secs(0),
+                previous_stack_depth: previous_stack_depth - removed_scene.is_some() as usize,
+                removed_scene,
+            })
+        } else {
+            None
+        };
+    }
+
+    // Call a function for every scene in the stack from the bottom up, including a scene that was
+    // removed but is still drawn by a transition
+    fn for_each_scene<F: FnMut(&mut dyn Scene<U>, &mut SceneControl<U>)>(
+        &mut self,
+        mut f: F,
+    ) {
+        for scene in self.scenes.iter_mut() {
+            f(scene.as_mut(), &mut self.scene_control);
+        }
+
+        if let Some(removed_scene) = self
+            .active_transition
+            .as_mut()
+            .and_then(|active_transition| active_transition.removed_scene.as_mut())
+        {
+            f(removed_scene.as_mut(), &mut self.scene_control);
+        }
+    }
+
+    // Advance the transition that is playing, ending it once it completes
+    fn advance_transition(
+        &mut self,
+        elapsed: Duration,
+    ) {
+        if let Some(active_transition) = &mut self.active_transition {
+            active_transition.elapsed += elapsed;
+            if active_transition.progress() >= 1.0 {
+                self.active_transition = None;
+            }
+        }
+    }
+
+    // Send the input events received during the frame to the top scene
+    fn send_input_events(
+        &mut self,
+        app_control: &mut AppControl,
+        events: &[TimedInputEvent],
+    ) {
+        if let Some(top) = self.scenes.last_mut() {
+            for event in events {
+                top.input_event(app_control, &mut self.scene_control, event);
+            }
+        }
+    }
+
+    // Draw the visible scenes among the bottom `depth` scenes of the stack, and optionally the
+    // scene that was removed on top of them
+    fn draw_scenes(
+        scenes: &mut [Box<dyn Scene<U>>],
+        removed_scene: Option<&mut Box<dyn Scene<U>>>,
+        draw_args: &mut AppDrawArgs,
+    ) {
+        // Start drawing from the top-most scene that isn't an overlay
+        let removed_is_opaque = removed_scene
+            .as_ref()
+            .map(|scene| !scene.is_overlay())
+            .unwrap_or(false);
+
+        let first_visible = if removed_is_opaque {
+            scenes.len()
+        } else {
+            scenes
+                .iter()
+                .rposition(|scene| !scene.is_overlay())
+                .unwrap_or(0)
+        };
+
+        for scene in scenes[first_visible..]
+            .iter_mut()
+            .chain(removed_scene.into_iter())
+        {
+            draw_args.canvas.save();
+            scene.draw(AppDrawArgs {
+                app_control: draw_args.app_control,
+                input_state: draw_args.input_state,
+                time_state: draw_args.time_state,
+                canvas: draw_args.canvas,
+                coordinate_system_helper: draw_args.coordinate_system_helper.clone(),
+            });
+            draw_args.canvas.restore();
+        }
+    }
+}
+
+impl<U: 'static> AppHandler<U> for SceneStack<U> {
+    fn update(
+        &mut self,
+        update_args: AppUpdateArgs,
+    ) {
+        let AppUpdateArgs {
+            app_control,
+            input_state,
+            time_state,
+            task_executor,
+            coordinate_system_helper,
+        } = update_args;
+
+        self.process_commands(app_control);
+        self.advance_transition(time_state.previous_update_time());
+        self.send_input_events(app_control, input_state.events());
+
+        if let Some(top) = self.scenes.last_mut() {
+            top.update(
+                AppUpdateArgs {
+                    app_control: &mut *app_control,
+                    input_state,
+                    time_state,
+                    task_executor,
+                    coordinate_system_helper,
+                },
+                &mut self.scene_control,
+            );
+        }
+
+        self.process_commands(app_control);
+    }
+
+    fn draw(
+        &mut self,
+        mut draw_args: AppDrawArgs,
+    ) {
+        let active_transition = match &mut self.active_transition {
+            Some(active_transition) => active_transition,
+            None => {
+                Self::draw_scenes(&mut self.scenes, None, &mut draw_args);
+                return;
+            }
+        };
+
+        let progress = active_transition.progress();
+        let previous_stack_depth = active_transition.previous_stack_depth;
+
+        match active_transition.transition {
+            SceneTransition::None => Self::draw_scenes(&mut self.scenes, None, &mut draw_args),
+            SceneTransition::Fade { color, .. } => {
+                // Fade out the old scenes during the first half, and fade in the new scenes during
+                // the second half
+                let alpha = if progress < 0.5 {
+                    Self::draw_scenes(
+                        &mut self.scenes[..previous_stack_depth],
+                        active_transition.removed_scene.as_mut(),
+                        &mut draw_args,
+                    );
+                    progress * 2.0
+                } else {
+                    Self::draw_scenes(&mut self.scenes, None, &mut draw_args);
+                    (1.0 - progress) * 2.0
+                };
+
+                let alpha = (alpha * color.a() as f32) as u8;
+                draw_args
+                    .canvas
+                    .draw_color(color.with_a(alpha), skia_safe::BlendMode::SrcOver);
+            }
+            SceneTransition::Crossfade { .. } => {
+                // The scenes below the change are drawn once. Only the removed scene and the added
+                // scenes are blended, otherwise the unchanged scenes would be drawn twice.
+                let (unchanged_scenes, added_scenes) =
+                    self.scenes.split_at_mut(previous_stack_depth);
+                Self::draw_scenes(unchanged_scenes, None, &mut draw_args);
+
+                if let Some(removed_scene) = active_transition.removed_scene.as_mut() {
+                    let alpha = ((1.0 - progress) * 255.0) as u32;
+                    draw_args.canvas.save_layer_alpha(None, alpha);
+                    Self::draw_scenes(&mut [], Some(removed_scene), &mut draw_args);
+                    draw_args.canvas.restore();
+                }
+
+                let alpha = (progress * 255.0) as u32;
+                draw_args.canvas.save_layer_alpha(None, alpha);
+                Self::draw_scenes(added_scenes, None, &mut draw_args);
+                draw_args.canvas.restore();
+            }
+        }
+    }
+
+    fn fatal_error(
+        &mut self,
+        error: &AppError,
+    ) {
+        for scene in self.scenes.iter_mut().rev() {
+            scene.fatal_error(error);
+        }
+    }
+
+    fn renderer_recreated(
+        &mut self,
+        app_control: &mut AppControl,
+    ) {
+        self.for_each_scene(|scene, scene_control| {
+            scene.renderer_recreated(app_control, scene_control)
+        });
+    }
+
+    fn shutdown(&mut self) {
+        // Commands queued now would never be processed
+        let mut app_control = AppControl::default();
+        let mut scene_control = SceneControl::<U>::default();
+        while let Some(mut scene) = self.scenes.pop() {
+            scene.on_exit(&mut app_control, &mut scene_control);
+        }
+    }
+
+    fn close_requested(
+        &mut self,
+        app_control: &mut AppControl,
+    ) -> bool {
+        match self.scenes.last_mut() {
+            Some(top) => top.close_requested(app_control, &mut self.scene_control),
+            None => true,
+        }
+    }
+
+    fn resized(
+        &mut self,
+        app_control: &mut AppControl,
+        inner_size: PhysicalSize<u32>,
+    ) {
+        self.for_each_scene(|scene, scene_control| {
+            scene.resized(app_control, scene_control, inner_size)
+        });
+    }
+
+    fn scale_factor_changed(
+        &mut self,
+        app_control: &mut AppControl,
+        scale_factor: f64,
+    ) {
+        self.for_each_scene(|scene, scene_control| {
+            scene.scale_factor_changed(app_control, scene_control, scale_factor)
+        });
+    }
+
+    fn monitor_changed(
+        &mut self,
+        app_control: &mut AppControl,
+        monitor: &MonitorInfo,
+    ) {
+        self.for_each_scene(|scene, scene_control| {
+            scene.monitor_changed(app_control, scene_control, monitor)
+        });
+    }
+
+    fn focus_gained(
+        &mut self,
+        app_control: &mut AppControl,
+    ) {
+        self.for_each_scene(|scene, scene_control| scene.focus_gained(app_control, scene_control));
+    }
+
+    fn focus_lost(
+        &mut self,
+        app_control: &mut AppControl,
+    ) {
+        self.for_each_scene(|scene, scene_control| scene.focus_lost(app_control, scene_control));
+    }
+
+    fn minimized(
+        &mut self,
+        app_control: &mut AppControl,
+    ) {
+        self.for_each_scene(|scene, scene_control| scene.minimized(app_control, scene_control));
+    }
+
+    fn restored(
+        &mut self,
+        app_control: &mut AppControl,
+    ) {
+        self.for_each_scene(|scene, scene_control| scene.restored(app_control, scene_control));
+    }
+
+    fn file_hovered(
+        &mut self,
+        app_control: &mut AppControl,
+        path: &Path,
+    ) {
+        if let Some(top) = self.scenes.last_mut() {
+            top.file_hovered(app_control, &mut self.scene_control, path);
+        }
+    }
+
+    fn file_hover_cancelled(
+        &mut self,
+        app_control: &mut AppControl,
+    ) {
+        if let Some(top) = self.scenes.last_mut() {
+            top.file_hover_cancelled(app_control, &mut self.scene_control);
+        }
+    }
+
+    fn file_dropped(
+        &mut self,
+        app_control: &mut AppControl,
+        path: &Path,
+    ) {
+        if let Some(top) = self.scenes.last_mut() {
+            top.file_dropped(app_control, &mut self.scene_control, path);
+        }
+    }
+
+    fn user_event(
+        &mut self,
+        app_control: &mut AppControl,
+        event: U,
+    ) {
+        if let Some(top) = self.scenes.last_mut() {
+            top.user_event(app_control, &mut self.scene_control, &event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_state::InputEvent;
+    use crate::input_state::PhysicalPosition;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Instant;
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    // Records every callback it receives. Optionally pushes another scene when it's entered.
+    struct RecordingScene {
+        name: &'static str,
+        log: Log,
+        push_on_enter: bool,
+    }
+
+    impl RecordingScene {
+        fn new(
+            name: &'static str,
+            log: &Log,
+        ) -> Self {
+            RecordingScene {
+                name,
+                log: log.clone(),
+                push_on_enter: false,
+            }
+        }
+
+        fn record(
+            &self,
+            callback: &str,
+        ) {
+            self.log
+                .borrow_mut()
+                .push(format!("{} {}", self.name, callback));
+        }
+    }
+
+    impl Scene<u32> for RecordingScene {
+        fn update(
+            &mut self,
+            _update_args: AppUpdateArgs,
+            _scene_control: &mut SceneControl<u32>,
+        ) {
+            self.record("update");
+        }
+
+        fn draw(
+            &mut self,
+            _draw_args: AppDrawArgs,
+        ) {
+            self.record("draw");
+        }
+
+        fn input_event(
+            &mut self,
+            _app_control: &mut AppControl,
+            _scene_control: &mut SceneControl<u32>,
+            _event: &TimedInputEvent,
+        ) {
+            self.record("input_event");
+        }
+
+        fn on_enter(
+            &mut self,
+            _app_control: &mut AppControl,
+            scene_control: &mut SceneControl<u32>,
+        ) {
+            self.record("on_enter");
+            if self.push_on_enter {
+                scene_control.push_scene(
+                    RecordingScene {
+                        name: self.name,
+                        log: self.log.clone(),
+                        push_on_enter: true,
+                    },
+                    SceneTransition::None,
+                );
+            }
+        }
+
+        fn on_exit(
+            &mut self,
+            _app_control: &mut AppControl,
+            _scene_control: &mut SceneControl<u32>,
+        ) {
+            self.record("on_exit");
+        }
+
+        fn on_pause(
+            &mut self,
+            _app_control: &mut AppControl,
+            _scene_control: &mut SceneControl<u32>,
+        ) {
+            self.record("on_pause");
+        }
+
+        fn on_resume(
+            &mut self,
+            _app_control: &mut AppControl,
+            _scene_control: &mut SceneControl<u32>,
+        ) {
+            self.record("on_resume");
+        }
+
+        fn user_event(
+            &mut self,
+            _app_control: &mut AppControl,
+            _scene_control: &mut SceneControl<u32>,
+            event: &u32,
+        ) {
+            self.record(&format!("user_event {}", event));
+        }
+
+        fn resized(
+            &mut self,
+            _app_control: &mut AppControl,
+            _scene_control: &mut SceneControl<u32>,
+            _inner_size: PhysicalSize<u32>,
+        ) {
+            self.record("resized");
+        }
+
+        fn file_dropped(
+            &mut self,
+            _app_control: &mut AppControl,
+            _scene_control: &mut SceneControl<u32>,
+            _path: &Path,
+        ) {
+            self.record("file_dropped");
+        }
+    }
+
+    fn take_log(log: &Log) -> Vec<String> {
+        std::mem::take(&mut *log.borrow_mut())
+    }
+
+    fn crossfade(millis: u64) -> SceneTransition {
+        SceneTransition::Crossfade {
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn push_pauses_and_pop_resumes() {
+        let log = Log::default();
+        let mut app_control = AppControl::default();
+        let mut scene_stack = SceneStack::new(RecordingScene::new("menu", &log));
+        scene_stack.process_commands(&mut app_control);
+        assert_eq!(take_log(&log), vec!["menu on_enter"]);
+
+        scene_stack
+            .scene_control
+            .push_scene(RecordingScene::new("game", &log), SceneTransition::None);
+        scene_stack.process_commands(&mut app_control);
+        assert_eq!(scene_stack.len(), 2);
+        assert_eq!(take_log(&log), vec!["menu on_pause", "game on_enter"]);
+
+        scene_stack.scene_control.pop_scene(SceneTransition::None);
+        scene_stack.process_commands(&mut app_control);
+        assert_eq!(scene_stack.len(), 1);
+        assert_eq!(take_log(&log), vec!["game on_exit", "menu on_resume"]);
+        assert!(!app_control.should_terminate_process());
+    }
+
+    #[test]
+    fn replace_exits_before_entering() {
+        let log = Log::default();
+        let mut app_control = AppControl::default();
+        let mut scene_stack = SceneStack::new(RecordingScene::new("menu", &log));
+        scene_stack.process_commands(&mut app_control);
+        take_log(&log);
+
+        scene_stack
+            .scene_control
+            .replace_scene(RecordingScene::new("game", &log), SceneTransition::None);
+        scene_stack.process_commands(&mut app_control);
+        assert_eq!(scene_stack.len(), 1);
+        assert_eq!(take_log(&log), vec!["menu on_exit", "game on_enter"]);
+    }
+
+    #[test]
+    fn popping_last_scene_terminates() {
+        let log = Log::default();
+        let mut app_control = AppControl::default();
+        let mut scene_stack = SceneStack::new(RecordingScene::new("menu", &log));
+        scene_stack.process_commands(&mut app_control);
+
+        scene_stack.scene_control.pop_scene(SceneTransition::None);
+        scene_stack.process_commands(&mut app_control);
+        assert!(scene_stack.is_empty());
+        assert!(app_control.should_terminate_process());
+    }
+
+    #[test]
+    fn commands_per_frame_are_capped() {
+        let log = Log::default();
+        let mut app_control = AppControl::default();
+        let mut scene_stack = SceneStack::new(RecordingScene {
+            push_on_enter: true,
+            ..RecordingScene::new("recursive", &log)
+        });
+
+        // Each scene pushes another scene when it's entered, so the commands never run out
+        scene_stack.process_commands(&mut app_control);
+        let len = scene_stack.len();
+        assert!(len > 1);
+        assert!(len <= SceneStack::<u32>::MAX_COMMANDS_PER_FRAME + 1);
+        assert!(scene_stack.scene_control.has_pending_commands());
+
+        // The remaining commands are processed in the next frame
+        scene_stack.process_commands(&mut app_control);
+        assert!(scene_stack.len() > len);
+    }
+
+    #[test]
+    fn input_only_reaches_top_scene() {
+        let log = Log::default();
+        let mut app_control = AppControl::default();
+        let mut scene_stack = SceneStack::new(RecordingScene::new("game", &log));
+        scene_stack
+            .scene_control
+            .push_scene(RecordingScene::new("pause", &log), SceneTransition::None);
+        scene_stack.process_commands(&mut app_control);
+        take_log(&log);
+
+        let event = TimedInputEvent {
+            event: InputEvent::MouseMoved,
+            position: PhysicalPosition::new(0.0, 0.0),
+            time: Instant::now(),
+        };
+        scene_stack.send_input_events(&mut app_control, &[event]);
+        scene_stack.file_dropped(&mut app_control, Path::new("file.txt"));
+        scene_stack.user_event(&mut app_control, 7);
+        assert_eq!(
+            take_log(&log),
+            vec![
+                "pause input_event",
+                "pause file_dropped",
+                "pause user_event 7"
+            ]
+        );
+
+        // Window events are sent to every scene
+        scene_stack.resized(&mut app_control, PhysicalSize::new(800, 600));
+        assert_eq!(take_log(&log), vec!["game resized", "pause resized"]);
+    }
+
+    #[test]
+    fn transition_completes_after_duration() {
+        let log = Log::default();
+        let mut app_control = AppControl::default();
+        let mut scene_stack = SceneStack::new(RecordingScene::new("game", &log));
+        scene_stack
+            .scene_control
+            .push_scene(RecordingScene::new("pause", &log), crossfade(100));
+        scene_stack.process_commands(&mut app_control);
+        assert!(scene_stack.is_transitioning());
+
+        scene_stack.advance_transition(Duration::from_millis(50));
+        let progress = scene_stack.active_transition.as_ref().unwrap().progress();
+        assert!((progress - 0.5).abs() < 0.01);
+
+        scene_stack.advance_transition(Duration::from_millis(60));
+        assert!(!scene_stack.is_transitioning());
+
+        // A transition without a duration completes immediately
+        scene_stack.scene_control.pop_scene(SceneTransition::None);
+        scene_stack.process_commands(&mut app_control);
+        assert!(!scene_stack.is_transitioning());
+    }
+
+    #[test]
+    fn removed_scene_is_kept_until_transition_completes() {
+        let log = Log::default();
+        let mut app_control = AppControl::default();
+        let mut scene_stack = SceneStack::new(RecordingScene::new("game", &log));
+        scene_stack
+            .scene_control
+            .push_scene(RecordingScene::new("pause", &log), SceneTransition::None);
+        scene_stack.process_commands(&mut app_control);
+
+        scene_stack.scene_control.pop_scene(crossfade(100));
+        scene_stack.process_commands(&mut app_control);
+        assert_eq!(scene_stack.len(), 1);
+        take_log(&log);
+
+        // The removed scene is still drawn, so it's still notified of window events
+        scene_stack.resized(&mut app_control, PhysicalSize::new(800, 600));
+        assert_eq!(take_log(&log), vec!["game resized", "pause resized"]);
+
+        scene_stack.advance_transition(Duration::from_millis(100));
+        scene_stack.resized(&mut app_control, PhysicalSize::new(800, 600));
+        assert_eq!(take_log(&log), vec!["game resized"]);
+    }
+}
@@ -0,0 +1,343 @@
+//! A lightweight executor for running futures on the main thread. Tasks are polled before each
+//! update, so they can create and hold non-Send objects like `skia_safe::Image`. Blocking work can
+//! be moved to another thread with `run_on_thread`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::task::Waker;
+
+type TaskId = u64;
+
+// Shared between the executor and the wakers it hands out, which may be used from other threads
+#[derive(Default)]
+struct TaskQueue {
+    // Tasks that have been woken and need to be polled
+    ready: Mutex<Vec<TaskId>>,
+
+    // True if the event loop has been woken but the executor has not polled yet. This avoids
+    // flooding the event loop with wake events.
+    wake_pending: AtomicBool,
+
+    // Wakes the event loop so that the executor gets polled
+    event_loop_waker: Mutex<Option<Arc<dyn Fn() + Send + Sync>>>,
+}
+
+impl TaskQueue {
+    fn wake(
+        &self,
+        task_id: TaskId,
+    ) {
+        self.ready.lock().unwrap().push(task_id);
+
+        if !self.wake_pending.swap(true, Ordering::AcqRel) {
+            let event_loop_waker = self.event_loop_waker.lock().unwrap().clone();
+            if let Some(event_loop_waker) = event_loop_waker {
+                (event_loop_waker)();
+            }
+        }
+    }
+}
+
+struct TaskWaker {
+    task_id: TaskId,
+    task_queue: Arc<TaskQueue>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.task_queue.wake(self.task_id);
+    }
+}
+
+/// Runs futures on the main thread. The app's executor is available from `AppUpdateArgs` and is
+/// polled before each update. When a task is woken, the event loop is woken as well.
+///
+/// Futures do not need to be `Send`, so they can produce objects that must stay on the main thread.
+#[derive(Default)]
+pub struct TaskExecutor {
+    tasks: HashMap<TaskId, Pin<Box<dyn Future<Output = ()>>>>,
+    next_task_id: TaskId,
+    task_queue: Arc<TaskQueue>,
+}
+
+impl TaskExecutor {
+    /// Create an executor that does not wake an event loop. Use `set_event_loop_waker` to do so.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the function called when a task is woken. This should wake the event loop so that the
+    /// executor is polled. It is called at most once between calls to `poll`.
+    pub fn set_event_loop_waker<F: Fn() + Send + Sync + 'static>(
+        &mut self,
+        event_loop_waker: F,
+    ) {
+        *self.task_queue.event_loop_waker.lock().unwrap() = Some(Arc::new(event_loop_waker));
+    }
+
+    /// Start running a future. It will be polled for the first time the next time the executor is
+    /// polled. The result can be retrieved from the returned handle once the future completes.
+    pub fn spawn<F>(
+        &mut self,
+        future: F,
+    ) -> TaskHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let result = Rc::new(RefCell::new(None));
+        let task_result = result.clone();
+        let task = async move {
+            let value = future.await;
+            *task_result.borrow_mut() = Some(value);
+        };
+
+        let task_id = self.next_task_id;
+        self.next_task_id += 1;
+
+        self.tasks.insert(task_id, Box::pin(task));
+        self.task_queue.wake(task_id);
+
+        TaskHandle { result }
+    }
+
+    /// Number of tasks that have not completed
+    pub fn task_count(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Polls every task that has been woken since the last poll. Tasks that complete are removed.
+    pub fn poll(&mut self) {
+        self.task_queue.wake_pending.store(false, Ordering::Release);
+        let ready = std::mem::take(&mut *self.task_queue.ready.lock().unwrap());
+
+        for task_id in ready {
+            // The task may have been woken more than once, and may have already completed
+            let task = match self.tasks.get_mut(&task_id) {
+                Some(task) => task,
+                None => continue,
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker {
+                task_id,
+                task_queue: self.task_queue.clone(),
+            }));
+            let mut context = Context::from_waker(&waker);
+
+            if task.as_mut().poll(&mut context).is_ready() {
+                trace!("task {} completed", task_id);
+                self.tasks.remove(&task_id);
+            }
+        }
+    }
+}
+
+/// Returned by `TaskExecutor::spawn`, used to retrieve the result of the task once it completes.
+/// Dropping the handle does not cancel the task.
+pub struct TaskHandle<R> {
+    result: Rc<RefCell<Option<R>>>,
+}
+
+impl<R> TaskHandle<R> {
+    /// Returns true if the task has completed and the result has not been taken yet
+    pub fn is_ready(&self) -> bool {
+        self.result.borrow().is_some()
+    }
+
+    /// Returns the result of the task if it has completed. The result can only be taken once.
+    pub fn try_take(&self) -> Option<R> {
+        self.result.borrow_mut().take()
+    }
+}
+
+// Shared between a ThreadTask and the thread that is running its closure
+struct ThreadTaskState<R> {
+    // Err if the closure panicked
+    result: Option<std::thread::Result<R>>,
+    waker: Option<Waker>,
+}
+
+/// A future that completes when a closure running on another thread returns. Created with
+/// `run_on_thread`.
+pub struct ThreadTask<R> {
+    state: Arc<Mutex<ThreadTaskState<R>>>,
+}
+
+impl<R> Future for ThreadTask<R> {
+    type Output = R;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        context: &mut Context,
+    ) -> Poll<R> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(panic)) => {
+                // Release the lock first so that it isn't poisoned
+                drop(state);
+                std::panic::resume_unwind(panic)
+            }
+            None => {
+                state.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Runs the given closure on a new thread. The returned future completes with the closure's
+/// result. This is intended for blocking work such as reading files.
+///
+/// If the closure panics, the panic is resumed on the main thread when the future is polled.
+pub fn run_on_thread<F, R>(f: F) -> ThreadTask<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let state = Arc::new(Mutex::new(ThreadTaskState {
+        result: None,
+        waker: None,
+    }));
+
+    let thread_state = state.clone();
+    std::thread::spawn(move || {
+        let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+
+        let waker = {
+            let mut state = thread_state.lock().unwrap();
+            state.result = Some(result);
+            state.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    });
+
+    ThreadTask { state }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    // Returns an executor whose event loop waker sends on the returned channel
+    fn executor() -> (TaskExecutor, mpsc::Receiver<()>) {
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+
+        let mut executor = TaskExecutor::new();
+        executor.set_event_loop_waker(move || {
+            let _ = sender.lock().unwrap().send(());
+        });
+
+        (executor, receiver)
+    }
+
+    // Polls the executor each time the event loop is woken, until the task completes
+    fn run_until_ready<R>(
+        executor: &mut TaskExecutor,
+        receiver: &mpsc::Receiver<()>,
+        handle: &TaskHandle<R>,
+    ) {
+        while !handle.is_ready() {
+            receiver
+                .recv_timeout(Duration::from_secs(10))
+                .expect("the event loop was not woken");
+            executor.poll();
+        }
+    }
+
+    #[test]
+    fn spawned_task_runs_on_poll() {
+        let mut executor = TaskExecutor::new();
+
+        // Futures don't need to be Send
+        let value = Rc::new(5);
+        let handle = executor.spawn(async move { *value * 2 });
+        assert!(!handle.is_ready());
+        assert_eq!(executor.task_count(), 1);
+
+        executor.poll();
+        assert_eq!(executor.task_count(), 0);
+        assert!(handle.is_ready());
+        assert_eq!(handle.try_take(), Some(10));
+        assert_eq!(handle.try_take(), None);
+    }
+
+    #[test]
+    fn event_loop_is_woken_once_between_polls() {
+        let wake_count = Arc::new(AtomicUsize::new(0));
+        let mut executor = TaskExecutor::new();
+        let executor_wake_count = wake_count.clone();
+        executor.set_event_loop_waker(move || {
+            executor_wake_count.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let first = executor.spawn(async { 1 });
+        let second = executor.spawn(async { 2 });
+        assert_eq!(wake_count.load(Ordering::SeqCst), 1);
+
+        executor.poll();
+        assert_eq!(first.try_take(), Some(1));
+        assert_eq!(second.try_take(), Some(2));
+
+        executor.spawn(async {});
+        assert_eq!(wake_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn thread_task_wakes_the_event_loop() {
+        let (mut executor, receiver) = executor();
+        let (sender, thread_receiver) = mpsc::channel::<()>();
+
+        let handle = executor.spawn(async move {
+            run_on_thread(move || {
+                thread_receiver.recv().unwrap();
+                42
+            })
+            .await
+        });
+
+        // The spawn wakes the event loop, and the first poll leaves the task waiting on the thread
+        receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        executor.poll();
+        assert!(!handle.is_ready());
+        assert_eq!(executor.task_count(), 1);
+
+        sender.send(()).unwrap();
+        run_until_ready(&mut executor, &receiver, &handle);
+        assert_eq!(handle.try_take(), Some(42));
+        assert_eq!(executor.task_count(), 0);
+    }
+
+    #[test]
+    fn thread_task_panic_is_resumed() {
+        let (mut executor, receiver) = executor();
+        let handle =
+            executor.spawn(async { run_on_thread(|| -> u32 { panic!("thread panicked") }).await });
+
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            run_until_ready(&mut executor, &receiver, &handle)
+        }));
+
+        let panic = result.unwrap_err();
+        assert_eq!(panic.downcast_ref::<&str>(), Some(&"thread panicked"));
+        assert!(!handle.is_ready());
+    }
+}
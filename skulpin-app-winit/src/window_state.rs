@@ -0,0 +1,477 @@
+//! Saves the window's position and size when the app exits so that it can be restored the next time
+//! the app runs. Enabled with `AppBuilder::persist_window_state`.
+
+use crate::winit;
+
+use winit::dpi::PhysicalPosition;
+use winit::dpi::PhysicalSize;
+use winit::monitor::MonitorHandle;
+use winit::window::Window;
+
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The minimum number of pixels of the window that must be on a monitor for a saved position to be
+/// restored. This keeps the titlebar reachable if monitors were rearranged.
+const MIN_VISIBLE_PIXELS: i64 = 64;
+
+/// The position and size of a window, as saved by `AppBuilder::persist_window_state`
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowState {
+    /// The top-left of the window including the titlebar, or None if the platform does not report
+    /// window positions (i.e. Wayland)
+    pub position: Option<PhysicalPosition<i32>>,
+
+    /// The inner size of the window when it is not maximized
+    pub inner_size: PhysicalSize<u32>,
+
+    /// Whether the window was maximized. This is only tracked with winit 0.25 and later.
+    pub maximized: bool,
+
+    /// The name of the monitor the window was on
+    pub monitor: Option<String>,
+}
+
+impl WindowState {
+    /// Read a window state from the given file. Returns None if the file does not exist or can't be
+    /// parsed.
+    pub fn load(path: &Path) -> Option<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                debug!("Could not read window state from {:?}: {}", path, e);
+                return None;
+            }
+        };
+
+        let window_state = Self::parse(&contents);
+        if window_state.is_none() {
+            warn!("Window state file {:?} does not contain a valid size", path);
+        }
+
+        window_state
+    }
+
+    /// Parse the contents of a window state file. Returns None if no valid size is found.
+    fn parse(contents: &str) -> Option<Self> {
+        let mut position = None;
+        let mut inner_size = None;
+        let mut maximized = false;
+        let mut monitor = None;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim();
+            let value = parts.next().unwrap_or("").trim();
+
+            match key {
+                "position" => {
+                    position = parse_pair(value).map(|(x, y)| PhysicalPosition::new(x, y))
+                }
+                "inner_size" => {
+                    inner_size = parse_pair(value).map(|(w, h)| PhysicalSize::new(w, h))
+                }
+                "maximized" => maximized = value == "true",
+                "monitor" => monitor = Some(value.to_string()),
+                _ => warn!("Unknown key {:?} in window state file", key),
+            }
+        }
+
+        match inner_size {
+            Some(inner_size) if inner_size.width > 0 && inner_size.height > 0 => {
+                Some(WindowState {
+                    position,
+                    inner_size,
+                    maximized,
+                    monitor,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Write the window state to the given file, creating the parent directory if necessary
+    pub fn save(
+        &self,
+        path: &Path,
+    ) -> std::io::Result<()> {
+        let mut contents = String::from("# Window state saved by skulpin\n");
+        if let Some(position) = self.position {
+            contents += &format!("position={},{}\n", position.x, position.y);
+        }
+
+        contents += &format!(
+            "inner_size={},{}\n",
+            self.inner_size.width, self.inner_size.height
+        );
+        contents += &format!("maximized={}\n", self.maximized);
+
+        if let Some(monitor) = &self.monitor {
+            contents += &format!("monitor={}\n", monitor);
+        }
+
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        std::fs::write(path, contents)
+    }
+
+    /// Adjust the state so that the window will be visible with the given monitors. The position is
+    /// dropped if the window would not be visible, and the size is limited to the size of the
+    /// monitor. If no monitors are reported, the state is returned unchanged.
+    pub fn validate<I: IntoIterator<Item = MonitorHandle>>(
+        mut self,
+        monitors: I,
+    ) -> Self {
+        let monitors: Vec<_> = monitors
+            .into_iter()
+            .map(|monitor| MonitorBounds::new(&monitor))
+            .collect();
+        self.validate_bounds(&monitors)
+    }
+
+    fn validate_bounds(
+        mut self,
+        monitors: &[MonitorBounds],
+    ) -> Self {
+        if monitors.is_empty() {
+            return self;
+        }
+
+        // Prefer the monitor the window was on. If it is no longer connected, any monitor will do
+        let saved_monitor = self.monitor.as_ref().and_then(|name| {
+            monitors
+                .iter()
+                .find(|monitor| monitor.name.as_ref() == Some(name))
+        });
+
+        let candidates: Vec<_> = match saved_monitor {
+            Some(saved_monitor) => vec![saved_monitor],
+            None => monitors.iter().collect(),
+        };
+
+        if let Some(position) = self.position {
+            let visible_monitor = candidates
+                .iter()
+                .find(|monitor| is_visible_on_monitor(position, self.inner_size, monitor));
+
+            match visible_monitor {
+                Some(monitor) => {
+                    self.inner_size = clamp_size(self.inner_size, monitor.size);
+                }
+                None => {
+                    debug!(
+                        "Saved window position {:?} is not visible on any monitor, ignoring it",
+                        position
+                    );
+                    self.position = None;
+                }
+            }
+        }
+
+        if self.position.is_none() {
+            // The window will be placed by the OS, so make sure it fits on the largest monitor
+            let largest_monitor_size = monitors
+                .iter()
+                .map(|monitor| monitor.size)
+                .max_by_key(|size| size.width as u64 * size.height as u64);
+
+            if let Some(largest_monitor_size) = largest_monitor_size {
+                self.inner_size = clamp_size(self.inner_size, largest_monitor_size);
+            }
+        }
+
+        self
+    }
+}
+
+/// The parts of a `MonitorHandle` that are needed to validate a window state
+#[derive(Clone, Debug)]
+struct MonitorBounds {
+    name: Option<String>,
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+}
+
+impl MonitorBounds {
+    fn new(monitor: &MonitorHandle) -> Self {
+        MonitorBounds {
+            name: monitor.name(),
+            position: monitor.position(),
+            size: monitor.size(),
+        }
+    }
+}
+
+fn parse_pair<T: std::str::FromStr>(value: &str) -> Option<(T, T)> {
+    let mut parts = value.splitn(2, ',');
+    let first = parts.next()?.trim().parse().ok()?;
+    let second = parts.next()?.trim().parse().ok()?;
+    Some((first, second))
+}
+
+fn clamp_size(
+    size: PhysicalSize<u32>,
+    max_size: PhysicalSize<u32>,
+) -> PhysicalSize<u32> {
+    PhysicalSize::new(
+        size.width.min(max_size.width.max(1)),
+        size.height.min(max_size.height.max(1)),
+    )
+}
+
+fn is_visible_on_monitor(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    monitor: &MonitorBounds,
+) -> bool {
+    let monitor_position = monitor.position;
+    let monitor_size = monitor.size;
+
+    let left = (position.x as i64).max(monitor_position.x as i64);
+    let top = (position.y as i64).max(monitor_position.y as i64);
+    let right = (position.x as i64 + size.width as i64)
+        .min(monitor_position.x as i64 + monitor_size.width as i64);
+    let bottom = (position.y as i64 + size.height as i64)
+        .min(monitor_position.y as i64 + monitor_size.height as i64);
+
+    right - left >= MIN_VISIBLE_PIXELS && bottom - top >= MIN_VISIBLE_PIXELS
+}
+
+/// Tracks the state of the window while the app runs and saves it when the app exits
+pub(crate) struct WindowStatePersistence {
+    path: PathBuf,
+    window_state: WindowState,
+}
+
+impl WindowStatePersistence {
+    pub(crate) fn new(
+        path: PathBuf,
+        window: &Window,
+    ) -> Self {
+        let mut persistence = WindowStatePersistence {
+            path,
+            window_state: WindowState {
+                position: None,
+                inner_size: window.inner_size(),
+                maximized: false,
+                monitor: None,
+            },
+        };
+
+        persistence.update(window);
+        persistence
+    }
+
+    /// Record the current state of the window. The size and position are only recorded while the
+    /// window is in its normal state, so that un-maximizing the window after it is restored
+    /// returns it to where it was.
+    pub(crate) fn update(
+        &mut self,
+        window: &Window,
+    ) {
+        if window.fullscreen().is_some() {
+            return;
+        }
+
+        #[cfg(any(feature = "winit-25", feature = "winit-latest"))]
+        {
+            self.window_state.maximized = window.is_maximized();
+        }
+
+        #[cfg(any(feature = "winit-21", feature = "winit-22"))]
+        let current_monitor = Some(window.current_monitor());
+
+        #[cfg(not(any(feature = "winit-21", feature = "winit-22")))]
+        let current_monitor = window.current_monitor();
+
+        if let Some(monitor) = current_monitor.and_then(|monitor| monitor.name()) {
+            self.window_state.monitor = Some(monitor);
+        }
+
+        // A zero size means the window is minimized
+        let inner_size = window.inner_size();
+        if self.window_state.maximized || inner_size.width == 0 || inner_size.height == 0 {
+            return;
+        }
+
+        self.window_state.inner_size = inner_size;
+        self.window_state.position = window.outer_position().ok();
+    }
+
+    pub(crate) fn save(&self) {
+        debug!("Saving window state to {:?}", self.path);
+        if let Err(e) = self.window_state.save(&self.path) {
+            warn!("Failed to save window state to {:?}: {}", self.path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(
+        name: &str,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    ) -> MonitorBounds {
+        MonitorBounds {
+            name: Some(name.to_string()),
+            position: PhysicalPosition::new(x, y),
+            size: PhysicalSize::new(width, height),
+        }
+    }
+
+    fn window_state(
+        position: Option<(i32, i32)>,
+        width: u32,
+        height: u32,
+    ) -> WindowState {
+        WindowState {
+            position: position.map(|(x, y)| PhysicalPosition::new(x, y)),
+            inner_size: PhysicalSize::new(width, height),
+            maximized: false,
+            monitor: None,
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("skulpin-window-state-{}", std::process::id()))
+            .join("window_state.txt");
+
+        let mut expected = window_state(Some((-20, 30)), 800, 600);
+        expected.maximized = true;
+        expected.monitor = Some("DP-1".to_string());
+
+        expected.save(&path).unwrap();
+        let loaded = WindowState::load(&path);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(loaded, Some(expected));
+    }
+
+    #[test]
+    fn load_missing_file() {
+        let path = std::env::temp_dir().join("skulpin-window-state-does-not-exist.txt");
+        assert_eq!(WindowState::load(&path), None);
+    }
+
+    #[test]
+    fn parse_ignores_malformed_entries() {
+        let contents = "\
+            # comment\n\
+            position=10\n\
+            inner_size = 640 , 480\n\
+            maximized=yes\n\
+            this line has no key\n\
+            unknown=1\n";
+
+        assert_eq!(
+            WindowState::parse(contents),
+            Some(window_state(None, 640, 480))
+        );
+    }
+
+    #[test]
+    fn parse_requires_a_valid_size() {
+        assert_eq!(WindowState::parse(""), None);
+        assert_eq!(WindowState::parse("position=10,10\n"), None);
+        assert_eq!(WindowState::parse("inner_size=0,480\n"), None);
+        assert_eq!(WindowState::parse("inner_size=-640,480\n"), None);
+        assert_eq!(WindowState::parse("inner_size=640,abc\n"), None);
+    }
+
+    #[test]
+    fn validate_without_monitors_is_unchanged() {
+        let state = window_state(Some((-5000, -5000)), 10000, 10000);
+        assert_eq!(state.clone().validate_bounds(&[]), state);
+    }
+
+    #[test]
+    fn validate_keeps_visible_position() {
+        let monitors = [monitor("A", 0, 0, 1920, 1080)];
+        let state = window_state(Some((100, 100)), 800, 600);
+        assert_eq!(state.clone().validate_bounds(&monitors), state);
+    }
+
+    #[test]
+    fn validate_drops_off_monitor_position() {
+        let monitors = [
+            monitor("A", 0, 0, 1920, 1080),
+            monitor("B", 1920, 0, 1280, 720),
+        ];
+
+        // Entirely off every monitor
+        let state = window_state(Some((5000, 5000)), 800, 600);
+        assert_eq!(
+            state.validate_bounds(&monitors),
+            window_state(None, 800, 600)
+        );
+
+        // Only a sliver of the window is on the monitor
+        let visible = MIN_VISIBLE_PIXELS as i32 - 1;
+        let state = window_state(Some((-800 + visible, 100)), 800, 600);
+        assert_eq!(
+            state.validate_bounds(&monitors),
+            window_state(None, 800, 600)
+        );
+
+        // Enough of the window is on the monitor
+        let visible = MIN_VISIBLE_PIXELS as i32;
+        let state = window_state(Some((-800 + visible, 100)), 800, 600);
+        assert_eq!(state.clone().validate_bounds(&monitors), state);
+    }
+
+    #[test]
+    fn validate_clamps_size_to_monitor() {
+        let monitors = [
+            monitor("A", 0, 0, 1920, 1080),
+            monitor("B", 1920, 0, 1280, 720),
+        ];
+
+        // Visible on the smaller monitor, so the size is limited to it
+        let state = window_state(Some((2000, 0)), 1600, 900);
+        assert_eq!(
+            state.validate_bounds(&monitors),
+            window_state(Some((2000, 0)), 1280, 720)
+        );
+
+        // The OS places the window, so the size is limited to the largest monitor
+        let state = window_state(None, 4000, 3000);
+        assert_eq!(
+            state.validate_bounds(&monitors),
+            window_state(None, 1920, 1080)
+        );
+    }
+
+    #[test]
+    fn validate_prefers_saved_monitor() {
+        let monitors = [
+            monitor("A", 0, 0, 1920, 1080),
+            monitor("B", 1920, 0, 1280, 720),
+        ];
+
+        // The position is on A, but the window was saved on B
+        let mut state = window_state(Some((100, 100)), 800, 600);
+        state.monitor = Some("B".to_string());
+        let validated = state.clone().validate_bounds(&monitors);
+        assert_eq!(validated.position, None);
+
+        // If the saved monitor is disconnected, any monitor will do
+        state.monitor = Some("C".to_string());
+        assert_eq!(state.clone().validate_bounds(&monitors), state);
+    }
+}
@@ -0,0 +1,214 @@
+use rafx::api::*;
+use rafx::api::ash;
+use ash::vk;
+use ash::version::InstanceV1_0;
+use std::ffi::CStr;
+
+use crate::SkulpinError;
+use crate::SkulpinResult;
+
+/// The kind of GPU an adapter is
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AdapterType {
+    /// A separate GPU. This is usually the fastest option.
+    Discrete,
+
+    /// A GPU built into the CPU. This usually uses less power than a discrete GPU.
+    Integrated,
+
+    /// A GPU exposed by a virtual machine
+    Virtual,
+
+    /// A software implementation that runs on the CPU, i.e. lavapipe or SwiftShader
+    Software,
+
+    /// Any other kind of device
+    Other,
+}
+
+impl AdapterType {
+    fn from_vk(device_type: vk::PhysicalDeviceType) -> Self {
+        match device_type {
+            vk::PhysicalDeviceType::DISCRETE_GPU => AdapterType::Discrete,
+            vk::PhysicalDeviceType::INTEGRATED_GPU => AdapterType::Integrated,
+            vk::PhysicalDeviceType::VIRTUAL_GPU => AdapterType::Virtual,
+            vk::PhysicalDeviceType::CPU => AdapterType::Software,
+            _ => AdapterType::Other,
+        }
+    }
+}
+
+impl core::fmt::Display for AdapterType {
+    fn fmt(
+        &self,
+        fmt: &mut core::fmt::Formatter,
+    ) -> core::fmt::Result {
+        let name = match *self {
+            AdapterType::Discrete => "discrete",
+            AdapterType::Integrated => "integrated",
+            AdapterType::Virtual => "virtual",
+            AdapterType::Software => "software",
+            AdapterType::Other => "other",
+        };
+
+        write!(fmt, "{}", name)
+    }
+}
+
+/// Describes a GPU (or software renderer) that vulkan can use. The `Display` impl prints a single
+/// line that is useful to include in logs and bug reports.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdapterInfo {
+    /// Position of the adapter in the list returned by `Renderer::adapters`
+    pub index: usize,
+
+    /// Name reported by the driver, i.e. "NVIDIA GeForce GTX 1070"
+    pub name: String,
+
+    /// PCI vendor ID, or a Khronos vendor ID for devices that aren't PCI devices
+    pub vendor_id: u32,
+
+    /// Device ID, chosen by the vendor
+    pub device_id: u32,
+
+    /// The kind of GPU
+    pub adapter_type: AdapterType,
+
+    /// Total size in bytes of the memory heaps that are local to the device
+    pub device_local_memory: u64,
+
+    /// Highest vulkan version supported by the device, i.e. "1.2.162"
+    pub vulkan_version: String,
+
+    /// Driver version. The encoding is vendor-specific.
+    pub driver_version: u32,
+}
+
+impl AdapterInfo {
+    /// Name of the vendor, if it's a well-known vendor ID
+    pub fn vendor_name(&self) -> Option<&'static str> {
+        match self.vendor_id {
+            0x1002 => Some("AMD"),
+            0x1010 => Some("ImgTec"),
+            0x106B => Some("Apple"),
+            0x10DE => Some("NVIDIA"),
+            0x13B5 => Some("ARM"),
+            0x5143 => Some("Qualcomm"),
+            0x8086 => Some("Intel"),
+            0x10005 => Some("Mesa"),
+            _ => None,
+        }
+    }
+
+    unsafe fn new(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+        index: usize,
+    ) -> Self {
+        let properties = instance.get_physical_device_properties(physical_device);
+        let memory_properties = instance.get_physical_device_memory_properties(physical_device);
+
+        let name = CStr::from_ptr(properties.device_name.as_ptr())
+            .to_string_lossy()
+            .into_owned();
+
+        let device_local_memory = memory_properties.memory_heaps
+            [..memory_properties.memory_heap_count as usize]
+            .iter()
+            .filter(|heap| heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL))
+            .map(|heap| heap.size)
+            .sum();
+
+        let vulkan_version = format!(
+            "{}.{}.{}",
+            vk::version_major(properties.api_version),
+            vk::version_minor(properties.api_version),
+            vk::version_patch(properties.api_version)
+        );
+
+        AdapterInfo {
+            index,
+            name,
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            adapter_type: AdapterType::from_vk(properties.device_type),
+            device_local_memory,
+            vulkan_version,
+            driver_version: properties.driver_version,
+        }
+    }
+
+    // Describes the device that the given device context is using
+    pub(crate) fn from_device_context(device_context: &RafxDeviceContext) -> SkulpinResult<Self> {
+        let (instance, physical_device) = vk_instance_and_physical_device(device_context)?;
+
+        unsafe {
+            let index = instance
+                .enumerate_physical_devices()
+                .map_err(RafxError::from)?
+                .iter()
+                .position(|x| *x == physical_device)
+                .unwrap_or(0);
+
+            Ok(AdapterInfo::new(instance, physical_device, index))
+        }
+    }
+}
+
+impl core::fmt::Display for AdapterInfo {
+    fn fmt(
+        &self,
+        fmt: &mut core::fmt::Formatter,
+    ) -> core::fmt::Result {
+        write!(fmt, "[{}] {} (", self.index, self.name)?;
+
+        match self.vendor_name() {
+            Some(vendor_name) => write!(fmt, "{}", vendor_name)?,
+            None => write!(fmt, "vendor 0x{:04X}", self.vendor_id)?,
+        }
+
+        write!(
+            fmt,
+            " device 0x{:04X}, {}, {} MiB, Vulkan {}, driver 0x{:X})",
+            self.device_id,
+            self.adapter_type,
+            self.device_local_memory / (1024 * 1024),
+            self.vulkan_version,
+            self.driver_version
+        )
+    }
+}
+
+// Lists the adapters visible to the vulkan instance the device context was created with
+pub(crate) fn enumerate_adapters(
+    device_context: &RafxDeviceContext
+) -> SkulpinResult<Vec<AdapterInfo>> {
+    let (instance, _) = vk_instance_and_physical_device(device_context)?;
+
+    unsafe {
+        let physical_devices = instance
+            .enumerate_physical_devices()
+            .map_err(RafxError::from)?;
+
+        Ok(physical_devices
+            .into_iter()
+            .enumerate()
+            .map(|(index, physical_device)| AdapterInfo::new(instance, physical_device, index))
+            .collect())
+    }
+}
+
+fn vk_instance_and_physical_device(
+    device_context: &RafxDeviceContext
+) -> SkulpinResult<(&ash::Instance, vk::PhysicalDevice)> {
+    let vk_device_context = device_context.vk_device_context().ok_or_else(|| {
+        SkulpinError::DeviceCreation(RafxError::StringError(
+            "The device context is not a vulkan device context".to_string(),
+        ))
+    })?;
+
+    Ok((
+        vk_device_context.instance(),
+        vk_device_context.physical_device(),
+    ))
+}
@@ -0,0 +1,312 @@
+//! A 2D camera that can pan, zoom, and rotate over a world. Use
+//! `CoordinateSystemHelper::use_camera` to apply it to a canvas.
+
+use super::CoordinateSystemHelper;
+
+/// A 2D camera looking at a world. The camera's position is drawn at the center of the surface.
+///
+/// Screen positions are in physical pixels relative to the top-left of the surface, which matches
+/// mouse positions reported by winit.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera2D {
+    position: skia_safe::Point,
+    zoom: f32,
+    rotation: f32,
+    y_up: bool,
+    min_zoom: f32,
+    max_zoom: f32,
+    bounds: Option<skia_safe::Rect>,
+}
+
+impl Default for Camera2D {
+    fn default() -> Self {
+        Camera2D::new()
+    }
+}
+
+impl Camera2D {
+    /// Create a camera centered on the origin, where one world unit is one logical pixel and +Y is
+    /// down
+    pub fn new() -> Self {
+        Camera2D {
+            position: skia_safe::Point::new(0.0, 0.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            y_up: false,
+            min_zoom: 0.0001,
+            max_zoom: 10000.0,
+            bounds: None,
+        }
+    }
+
+    /// The point in the world that is drawn at the center of the surface
+    pub fn position(&self) -> skia_safe::Point {
+        self.position
+    }
+
+    /// Move the camera. The position is clamped to the bounds, if any.
+    pub fn set_position<P: Into<skia_safe::Point>>(
+        &mut self,
+        position: P,
+    ) {
+        self.position = position.into();
+        self.clamp_position();
+    }
+
+    /// The number of logical pixels per world unit
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Set the number of logical pixels per world unit. This is clamped to the zoom limits.
+    pub fn set_zoom(
+        &mut self,
+        zoom: f32,
+    ) {
+        self.zoom = zoom.max(self.min_zoom).min(self.max_zoom);
+    }
+
+    /// Limit the zoom to the given range. Both values must be positive.
+    pub fn set_zoom_limits(
+        &mut self,
+        min_zoom: f32,
+        max_zoom: f32,
+    ) {
+        self.min_zoom = min_zoom.max(std::f32::EPSILON);
+        self.max_zoom = max_zoom.max(self.min_zoom);
+        self.set_zoom(self.zoom);
+    }
+
+    /// Rotation of the view in radians. The world appears rotated by the opposite angle.
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    pub fn set_rotation(
+        &mut self,
+        rotation: f32,
+    ) {
+        self.rotation = rotation;
+    }
+
+    /// Returns true if +Y points up in the world
+    pub fn is_y_up(&self) -> bool {
+        self.y_up
+    }
+
+    /// If true, +Y points up in the world rather than down. Note that text and images will be drawn
+    /// upside down unless they are flipped.
+    pub fn set_y_up(
+        &mut self,
+        y_up: bool,
+    ) {
+        self.y_up = y_up;
+    }
+
+    /// The area the camera's position is kept within, if any
+    pub fn bounds(&self) -> Option<skia_safe::Rect> {
+        self.bounds
+    }
+
+    /// Keep the camera's position within the given area of the world
+    pub fn set_bounds(
+        &mut self,
+        bounds: Option<skia_safe::Rect>,
+    ) {
+        self.bounds = bounds;
+        self.clamp_position();
+    }
+
+    /// Move the camera by the given distance in world units
+    pub fn pan<V: Into<skia_safe::Vector>>(
+        &mut self,
+        delta: V,
+    ) {
+        let delta = delta.into();
+        self.set_position(self.position + delta);
+    }
+
+    /// Move the camera so that the world follows a drag of the given distance in physical pixels,
+    /// i.e. the mouse movement while a button is held
+    pub fn pan_by_screen_delta<V: Into<skia_safe::Vector>>(
+        &mut self,
+        coordinate_system_helper: &CoordinateSystemHelper,
+        screen_delta: V,
+    ) {
+        if let Some(inverse) = coordinate_system_helper.camera_matrix(self).invert() {
+            let world_delta = inverse.map_vector(screen_delta.into());
+            self.pan(-world_delta);
+        }
+    }
+
+    /// Multiply the zoom by the given factor, keeping the world position under the given screen
+    /// position in place. This is commonly used to zoom toward the mouse cursor.
+    pub fn zoom_at<P: Into<skia_safe::Point>>(
+        &mut self,
+        coordinate_system_helper: &CoordinateSystemHelper,
+        screen_position: P,
+        factor: f32,
+    ) {
+        let screen_position = screen_position.into();
+        let before = self.screen_to_world(coordinate_system_helper, screen_position);
+
+        self.set_zoom(self.zoom * factor);
+
+        let after = self.screen_to_world(coordinate_system_helper, screen_position);
+        if let (Some(before), Some(after)) = (before, after) {
+            self.pan(before - after);
+        }
+    }
+
+    /// Move the camera toward the target. `speed` controls how quickly the camera catches up, and
+    /// is independent of frame rate. Larger values are faster, and around 5.0 is a gentle follow.
+    pub fn follow<P: Into<skia_safe::Point>>(
+        &mut self,
+        target: P,
+        speed: f32,
+        dt: f32,
+    ) {
+        let target = target.into();
+        let t = 1.0 - (-speed * dt).exp();
+        self.set_position(self.position + (target - self.position) * t);
+    }
+
+    /// Center the camera on the given area of the world and zoom so that it is fully visible
+    pub fn zoom_to_fit(
+        &mut self,
+        coordinate_system_helper: &CoordinateSystemHelper,
+        world_rect: skia_safe::Rect,
+    ) {
+        let logical_size = coordinate_system_helper.window_logical_size();
+        if world_rect.width() <= 0.0 || world_rect.height() <= 0.0 {
+            return;
+        }
+
+        let zoom_x = logical_size.width as f32 / world_rect.width();
+        let zoom_y = logical_size.height as f32 / world_rect.height();
+        self.set_zoom(zoom_x.min(zoom_y));
+        self.set_position(world_rect.center());
+    }
+
+    /// Convert a position in physical pixels to a position in the world. Returns None if the
+    /// camera matrix can't be inverted (i.e. the surface has zero size)
+    pub fn screen_to_world<P: Into<skia_safe::Point>>(
+        &self,
+        coordinate_system_helper: &CoordinateSystemHelper,
+        screen_position: P,
+    ) -> Option<skia_safe::Point> {
+        coordinate_system_helper
+            .camera_matrix(self)
+            .invert()
+            .map(|inverse| inverse.map_point(screen_position))
+    }
+
+    /// Convert a position in the world to a position in physical pixels
+    pub fn world_to_screen<P: Into<skia_safe::Point>>(
+        &self,
+        coordinate_system_helper: &CoordinateSystemHelper,
+        world_position: P,
+    ) -> skia_safe::Point {
+        coordinate_system_helper
+            .camera_matrix(self)
+            .map_point(world_position)
+    }
+
+    /// The area of the world that is visible. If the camera is rotated, this is the bounding box of
+    /// the visible area.
+    pub fn visible_world_rect(
+        &self,
+        coordinate_system_helper: &CoordinateSystemHelper,
+    ) -> Option<skia_safe::Rect> {
+        let surface_rect = skia_safe::Rect::from(coordinate_system_helper.viewport_rect());
+
+        coordinate_system_helper
+            .camera_matrix(self)
+            .invert()
+            .map(|inverse| inverse.map_rect(surface_rect).0)
+    }
+
+    fn clamp_position(&mut self) {
+        if let Some(bounds) = self.bounds {
+            self.position.x = self.position.x.max(bounds.left).min(bounds.right);
+            self.position.y = self.position.y.max(bounds.top).min(bounds.bottom);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rafx::api::RafxExtents2D;
+
+    fn helper() -> CoordinateSystemHelper {
+        CoordinateSystemHelper::new(
+            RafxExtents2D {
+                width: 800,
+                height: 600,
+            },
+            2.0,
+        )
+    }
+
+    fn assert_near(
+        actual: skia_safe::Point,
+        expected: skia_safe::Point,
+    ) {
+        assert!(
+            (actual.x - expected.x).abs() < 1e-3 && (actual.y - expected.y).abs() < 1e-3,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn zoom_at_keeps_cursor_fixed() {
+        let helper = helper();
+        let mut camera = Camera2D::new();
+        camera.set_position((25.0, -40.0));
+        camera.set_rotation(0.3);
+
+        for &(cursor, factor) in &[
+            (skia_safe::Point::new(100.0, 50.0), 2.0),
+            (skia_safe::Point::new(700.0, 500.0), 0.25),
+            (skia_safe::Point::new(400.0, 300.0), 3.0),
+        ] {
+            let before = camera.screen_to_world(&helper, cursor).unwrap();
+            let zoom = camera.zoom();
+            camera.zoom_at(&helper, cursor, factor);
+
+            assert!((camera.zoom() - zoom * factor).abs() < 1e-3);
+            assert_near(camera.screen_to_world(&helper, cursor).unwrap(), before);
+            assert_near(camera.world_to_screen(&helper, before), cursor);
+        }
+    }
+
+    #[test]
+    fn zoom_at_keeps_cursor_fixed_with_y_up() {
+        let helper = helper();
+        let mut camera = Camera2D::new();
+        camera.set_y_up(true);
+
+        let cursor = skia_safe::Point::new(600.0, 100.0);
+        let before = camera.screen_to_world(&helper, cursor).unwrap();
+        camera.zoom_at(&helper, cursor, 4.0);
+
+        assert_near(camera.screen_to_world(&helper, cursor).unwrap(), before);
+    }
+
+    #[test]
+    fn zoom_at_respects_zoom_limits() {
+        let helper = helper();
+        let mut camera = Camera2D::new();
+        camera.set_zoom_limits(0.5, 2.0);
+
+        let cursor = skia_safe::Point::new(100.0, 100.0);
+        let before = camera.screen_to_world(&helper, cursor).unwrap();
+        camera.zoom_at(&helper, cursor, 10.0);
+
+        assert_eq!(camera.zoom(), 2.0);
+        assert_near(camera.screen_to_world(&helper, cursor).unwrap(), before);
+    }
+}
@@ -2,6 +2,8 @@
 
 use rafx::api::RafxExtents2D;
 
+use super::Camera2D;
+
 /// A size in raw pixels
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct PhysicalSize {
@@ -122,6 +124,28 @@ pub enum CoordinateSystem {
     /// for the current aspect ratio
     FixedWidth(skia_safe::Point, f32),
 
+    /// Logical coordinates with (0,0) at the center of the window, +X right and +Y down
+    Centered,
+
+    /// Logical coordinates with (0,0) at the center of the window, +X right and +Y up. Use
+    /// `draw_str_upright` and `draw_image_upright` to keep text and images readable.
+    CenteredYUp,
+
+    /// Logical coordinates with (0,0) at the bottom-left of the window, +X right and +Y up. Use
+    /// `draw_str_upright` and `draw_image_upright` to keep text and images readable.
+    BottomLeftYUp,
+
+    /// Draw to an offscreen canvas of `virtual_size` pixels, then scale it to the window by the
+    /// largest whole number that fits. The remainder of the window is filled with
+    /// `letterbox_color`. The image is scaled without filtering so that pixel art stays sharp.
+    /// Canvas coordinates are pixels of the virtual canvas with (0,0) at the top-left. The helper
+    /// passed to the draw callback describes the virtual canvas, while `screen_to_canvas` on a
+    /// helper for the window maps window pixels to virtual pixels.
+    PixelPerfect {
+        virtual_size: PhysicalSize,
+        letterbox_color: skia_safe::Color,
+    },
+
     /// Do not modify the canvas matrix
     None,
 }
@@ -132,6 +156,78 @@ impl Default for CoordinateSystem {
     }
 }
 
+impl CoordinateSystem {
+    /// Returns true if +Y points up on the screen. This does not mean the canvas is mirrored, as a
+    /// range that is also flipped horizontally is rotated instead. Use `is_canvas_mirrored` to
+    /// check whether text and images need to be flipped.
+    pub fn is_y_up(&self) -> bool {
+        match self {
+            CoordinateSystem::CenteredYUp | CoordinateSystem::BottomLeftYUp => true,
+            CoordinateSystem::VisibleRange(visible_range, _) => {
+                visible_range.top > visible_range.bottom
+            }
+            // The Y extents have the same sign as the X extents
+            CoordinateSystem::FixedWidth(_, x_half_extents) => *x_half_extents < 0.0,
+            _ => false,
+        }
+    }
+}
+
+/// Returned when a coordinate system can't be mapped to the surface
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CoordinateSystemError {
+    /// The visible range has no area, or the surface has no area (i.e. the window is minimized)
+    EmptyRange,
+
+    /// The matrix can't be inverted, so positions on the surface can't be mapped to the canvas
+    NotInvertible,
+
+    /// `CoordinateSystem::None` leaves the matrix to the caller, so it is not known
+    UnknownMatrix,
+}
+
+impl std::error::Error for CoordinateSystemError {}
+
+impl core::fmt::Display for CoordinateSystemError {
+    fn fmt(
+        &self,
+        fmt: &mut core::fmt::Formatter,
+    ) -> core::fmt::Result {
+        match self {
+            CoordinateSystemError::EmptyRange => {
+                write!(fmt, "The visible range or the surface has no area")
+            }
+            CoordinateSystemError::NotInvertible => {
+                write!(fmt, "The coordinate system matrix can't be inverted")
+            }
+            CoordinateSystemError::UnknownMatrix => {
+                write!(fmt, "The matrix for CoordinateSystem::None is not known")
+            }
+        }
+    }
+}
+
+/// The area of the surface that shows a coordinate system's content, and the unused bars around it.
+/// For example, `ScaleToFit::Center` leaves bars on two sides when the aspect ratio of the visible
+/// range does not match the surface. UI can be placed within `content_rect` to avoid the bars.
+///
+/// All rectangles are in raw pixels relative to the top-left of the surface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Letterbox {
+    /// The area of the surface that the coordinate system's content is drawn to
+    pub content_rect: skia_safe::Rect,
+
+    /// The areas of the surface outside of `content_rect`. Empty bars are not included.
+    pub bars: Vec<skia_safe::Rect>,
+}
+
+impl Letterbox {
+    /// Returns true if the content does not fill the whole area
+    pub fn has_bars(&self) -> bool {
+        !self.bars.is_empty()
+    }
+}
+
 /// Provides a convenient method to set the canvas coordinate system to commonly-desired defaults.
 ///
 /// * Physical coordinates will use (0,0) top-left and (+X,+Y) right-bottom where X/Y is the raw
@@ -147,14 +243,20 @@ impl Default for CoordinateSystem {
 /// * See `use_physical_coordinates`, `use_logical_coordinates`, or `use_visible_range` to choose
 ///   between these options.
 ///
+/// A helper can also be restricted to a sub-rectangle of the surface with `viewport`. All of the
+/// coordinate systems then map to that rectangle instead of the whole surface. This is useful for
+/// split-screen, minimaps, or picture-in-picture. See `draw_viewport`.
+///
 /// For custom behavior, it's always possible to call `canvas.reset_matrix()` and set up the matrix
 /// manually
 #[derive(Clone)]
 pub struct CoordinateSystemHelper {
     surface_extents: RafxExtents2D,
+    viewport_origin: skia_safe::IPoint,
     window_logical_size: LogicalSize,
     window_physical_size: PhysicalSize,
     scale_factor: f64,
+    coordinate_system: CoordinateSystem,
 }
 
 impl CoordinateSystemHelper {
@@ -172,12 +274,138 @@ impl CoordinateSystemHelper {
 
         CoordinateSystemHelper {
             surface_extents,
+            viewport_origin: skia_safe::IPoint::new(0, 0),
             window_logical_size,
             window_physical_size,
             scale_factor,
+            coordinate_system: CoordinateSystem::default(),
         }
     }
 
+    /// Create a helper that maps coordinate systems to the given rectangle of the surface rather
+    /// than the whole surface. The rectangle is in raw pixels relative to the top-left of the
+    /// surface. For the returned helper, `surface_extents` and the window sizes are the size of the
+    /// rectangle.
+    pub fn viewport(
+        &self,
+        rect: skia_safe::IRect,
+    ) -> Result<CoordinateSystemHelper, CoordinateSystemError> {
+        if rect.is_empty() {
+            return Err(CoordinateSystemError::EmptyRange);
+        }
+
+        let surface_extents = RafxExtents2D {
+            width: rect.width() as u32,
+            height: rect.height() as u32,
+        };
+
+        let window_physical_size = PhysicalSize::new(surface_extents.width, surface_extents.height);
+        let window_logical_size = window_physical_size.to_logical(self.scale_factor);
+
+        Ok(CoordinateSystemHelper {
+            surface_extents,
+            viewport_origin: skia_safe::IPoint::new(rect.left, rect.top),
+            window_logical_size,
+            window_physical_size,
+            scale_factor: self.scale_factor,
+            coordinate_system: self.coordinate_system,
+        })
+    }
+
+    /// The area of the surface this helper maps to, in raw pixels. This is the whole surface unless
+    /// the helper was created with `viewport`.
+    pub fn viewport_rect(&self) -> skia_safe::IRect {
+        skia_safe::IRect::from_xywh(
+            self.viewport_origin.x,
+            self.viewport_origin.y,
+            self.surface_extents.width as i32,
+            self.surface_extents.height as i32,
+        )
+    }
+
+    /// Convert a rectangle given as fractions of this helper's area to raw pixels. For example,
+    /// (0.75, 0.0, 1.0, 0.25) is the top-right corner, which could be used for a minimap.
+    pub fn fractional_viewport_rect(
+        &self,
+        fractions: skia_safe::Rect,
+    ) -> skia_safe::IRect {
+        let viewport_rect = self.viewport_rect();
+        let width = viewport_rect.width() as f32;
+        let height = viewport_rect.height() as f32;
+
+        skia_safe::IRect::new(
+            viewport_rect.left + (fractions.left * width).round() as i32,
+            viewport_rect.top + (fractions.top * height).round() as i32,
+            viewport_rect.left + (fractions.right * width).round() as i32,
+            viewport_rect.top + (fractions.bottom * height).round() as i32,
+        )
+    }
+
+    /// Split this helper's area into a grid of equally-sized rectangles, in raw pixels. The
+    /// rectangles are returned row by row. For example, `grid_viewport_rects(2, 1)` splits the
+    /// area into left and right halves for two player split-screen.
+    pub fn grid_viewport_rects(
+        &self,
+        columns: u32,
+        rows: u32,
+    ) -> Vec<skia_safe::IRect> {
+        let mut rects = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            for column in 0..columns {
+                rects.push(self.fractional_viewport_rect(skia_safe::Rect::new(
+                    column as f32 / columns as f32,
+                    row as f32 / rows as f32,
+                    (column + 1) as f32 / columns as f32,
+                    (row + 1) as f32 / rows as f32,
+                )));
+            }
+        }
+
+        rects
+    }
+
+    /// Draw into the given rectangle of the surface with its own coordinate system. Drawing is
+    /// clipped to the rectangle, and the canvas state is restored afterwards. The closure is passed
+    /// a helper for the viewport, which can be used to convert positions or to nest viewports.
+    ///
+    /// `CoordinateSystem::PixelPerfect` uses the whole number scale but is not drawn to an
+    /// offscreen canvas, so images drawn in the viewport are still filtered.
+    pub fn draw_viewport<F: FnOnce(&mut skia_safe::Canvas, CoordinateSystemHelper)>(
+        &self,
+        canvas: &mut skia_safe::Canvas,
+        rect: skia_safe::IRect,
+        coordinate_system: CoordinateSystem,
+        f: F,
+    ) -> Result<(), CoordinateSystemError> {
+        let helper = self
+            .viewport(rect)?
+            .with_coordinate_system(coordinate_system);
+
+        canvas.save();
+
+        // The clip is transformed by the current matrix, so clear it first
+        canvas.reset_matrix();
+        canvas.clip_irect(rect, None);
+
+        let result = helper.use_coordinate_system(canvas);
+        if result.is_ok() {
+            f(canvas, helper);
+        }
+
+        canvas.restore();
+        result
+    }
+
+    /// Set the coordinate system used by `screen_to_canvas` and `canvas_to_screen`. The renderer
+    /// sets this to the coordinate system it applies to the canvas before drawing.
+    pub fn with_coordinate_system(
+        mut self,
+        coordinate_system: CoordinateSystem,
+    ) -> Self {
+        self.coordinate_system = coordinate_system;
+        self
+    }
+
     /// Get the raw pixel size of the surface to which we are drawing
     pub fn surface_extents(&self) -> RafxExtents2D {
         self.surface_extents
@@ -199,13 +427,36 @@ impl CoordinateSystemHelper {
         self.scale_factor
     }
 
+    /// The coordinate system used by `screen_to_canvas` and `canvas_to_screen`. This is the
+    /// coordinate system that was applied to the canvas before drawing. It does not reflect changes
+    /// made to the canvas matrix afterwards (i.e. by calling `use_visible_range`)
+    pub fn coordinate_system(&self) -> CoordinateSystem {
+        self.coordinate_system
+    }
+
     /// Use raw pixels for the coordinate system. Top-left is (0, 0), bottom-right is (+X, +Y)
     pub fn use_physical_coordinates(
         &self,
         canvas: &mut skia_safe::Canvas,
     ) {
-        // For raw physical pixels, no need to do anything
-        canvas.reset_matrix();
+        canvas.set_matrix(&self.physical_coordinates_matrix().into());
+    }
+
+    /// Set the canvas matrix for the coordinate system returned by `coordinate_system`.
+    /// `CoordinateSystem::None` leaves the canvas unchanged.
+    pub fn use_coordinate_system(
+        &self,
+        canvas: &mut skia_safe::Canvas,
+    ) -> Result<(), CoordinateSystemError> {
+        match self.coordinate_system {
+            CoordinateSystem::None => {}
+            coordinate_system => {
+                let m = self.coordinate_system_matrix(coordinate_system)?;
+                canvas.set_matrix(&m.into());
+            }
+        }
+
+        Ok(())
     }
 
     /// Use logical coordinates for the coordinate system. Top-left is (0, 0), bottom-right is
@@ -215,17 +466,25 @@ impl CoordinateSystemHelper {
         &self,
         canvas: &mut skia_safe::Canvas,
     ) {
-        // To handle hi-dpi displays, we need to compare the logical size of the window with the
-        // actual canvas size. Critically, the canvas size won't necessarily be the size of the
-        // window in physical pixels.
-        let scale = (
-            (f64::from(self.surface_extents.width) / self.window_logical_size.width as f64) as f32,
-            (f64::from(self.surface_extents.height) / self.window_logical_size.height as f64)
-                as f32,
-        );
+        canvas.set_matrix(&self.logical_coordinates_matrix().into());
+    }
 
-        canvas.reset_matrix();
-        canvas.scale(scale);
+    /// Use logical coordinates with (0,0) at the center of the window. If `y_up` is true, +Y points
+    /// up rather than down.
+    pub fn use_centered_coordinates(
+        &self,
+        canvas: &mut skia_safe::Canvas,
+        y_up: bool,
+    ) {
+        canvas.set_matrix(&self.centered_coordinates_matrix(y_up).into());
+    }
+
+    /// Use logical coordinates with (0,0) at the bottom-left of the window and +Y pointing up
+    pub fn use_bottom_left_y_up_coordinates(
+        &self,
+        canvas: &mut skia_safe::Canvas,
+    ) {
+        canvas.set_matrix(&self.bottom_left_y_up_coordinates_matrix().into());
     }
 
     /// Maps the given visible range to the render surface. For example, if you want a coordinate
@@ -239,16 +498,283 @@ impl CoordinateSystemHelper {
     ///
     /// Skia assumes that left is less than right and that top is less than bottom. If you provide
     /// a visible range that violates this, this function will apply a scaling factor to try to
-    /// provide intuitive behavior. However, this can have side effects like upside-down text.
+    /// provide intuitive behavior. However, this can have side effects like upside-down text. Use
+    /// `draw_str_upright` and `draw_image_upright` to counter this.
     ///
     /// See https://skia.org/user/api/SkMatrix_Reference#SkMatrix_setRectToRect
     /// See https://skia.org/user/api/SkMatrix_Reference#SkMatrix_ScaleToFit
     pub fn use_visible_range(
         &self,
         canvas: &mut skia_safe::Canvas,
+        visible_range: skia_safe::Rect,
+        scale_to_fit: skia_safe::matrix::ScaleToFit,
+    ) -> Result<(), CoordinateSystemError> {
+        let m = self.visible_range_matrix(visible_range, scale_to_fit)?;
+        canvas.set_matrix(&m.into());
+        Ok(())
+    }
+
+    /// Given a center position and half-extents for X, calculate an appropriate Y half-extents that
+    /// is consistent with the aspect ratio.
+    pub fn use_fixed_width(
+        &self,
+        canvas: &mut skia_safe::Canvas,
+        center: skia_safe::Point,
+        x_half_extents: f32,
+    ) -> Result<(), CoordinateSystemError> {
+        let m = self.fixed_width_matrix(center, x_half_extents)?;
+        canvas.set_matrix(&m.into());
+        Ok(())
+    }
+
+    /// Set the canvas matrix to view the world through the given camera
+    pub fn use_camera(
+        &self,
+        canvas: &mut skia_safe::Canvas,
+        camera: &Camera2D,
+    ) {
+        canvas.set_matrix(&self.camera_matrix(camera).into());
+    }
+
+    /// Returns the matrix that maps world coordinates seen by the given camera to raw pixels on the
+    /// surface. The camera's position is mapped to the center of the surface.
+    pub fn camera_matrix(
+        &self,
+        camera: &Camera2D,
+    ) -> skia_safe::Matrix {
+        // Zoom is measured in logical pixels, so include the hi-dpi scale
+        let logical_scale =
+            self.surface_extents.width as f32 / (self.window_logical_size.width.max(1)) as f32;
+        let scale = camera.zoom() * logical_scale;
+        let y_scale = if camera.is_y_up() { -scale } else { scale };
+
+        let position = camera.position();
+        let mut m = skia_safe::Matrix::translate((
+            self.viewport_origin.x as f32 + self.surface_extents.width as f32 / 2.0,
+            self.viewport_origin.y as f32 + self.surface_extents.height as f32 / 2.0,
+        ));
+        m.pre_scale((scale, y_scale), None);
+        m.pre_rotate(-camera.rotation().to_degrees(), None);
+        m.pre_translate((-position.x, -position.y));
+        m
+    }
+
+    /// Returns the matrix that the renderer would apply to the canvas for the given coordinate
+    /// system. This maps canvas coordinates to raw pixels on the surface, so its inverse can be
+    /// used to convert a mouse position into canvas coordinates.
+    ///
+    /// Returns an error for `CoordinateSystem::None` because the matrix is not known, or if the
+    /// coordinate system cannot be mapped to the surface (i.e. an empty visible range)
+    pub fn coordinate_system_matrix(
+        &self,
+        coordinate_system: CoordinateSystem,
+    ) -> Result<skia_safe::Matrix, CoordinateSystemError> {
+        match coordinate_system {
+            CoordinateSystem::None => Err(CoordinateSystemError::UnknownMatrix),
+            CoordinateSystem::Physical => Ok(self.physical_coordinates_matrix()),
+            CoordinateSystem::Logical => Ok(self.logical_coordinates_matrix()),
+            CoordinateSystem::Centered => Ok(self.centered_coordinates_matrix(false)),
+            CoordinateSystem::CenteredYUp => Ok(self.centered_coordinates_matrix(true)),
+            CoordinateSystem::BottomLeftYUp => Ok(self.bottom_left_y_up_coordinates_matrix()),
+            CoordinateSystem::PixelPerfect { virtual_size, .. } => {
+                self.pixel_perfect_matrix(virtual_size)
+            }
+            CoordinateSystem::VisibleRange(visible_range, scale_to_fit) => {
+                self.visible_range_matrix(visible_range, scale_to_fit)
+            }
+            CoordinateSystem::FixedWidth(center, x_half_extents) => {
+                self.fixed_width_matrix(center, x_half_extents)
+            }
+        }
+    }
+
+    /// Returns the area of the surface that shows the content of the coordinate system returned by
+    /// `coordinate_system`, and the letterbox bars around it. Only `CoordinateSystem::VisibleRange`
+    /// with `ScaleToFit::Center`, `Start` or `End`, and `CoordinateSystem::PixelPerfect` leave bars.
+    pub fn letterbox(&self) -> Result<Letterbox, CoordinateSystemError> {
+        let viewport_rect = skia_safe::Rect::from(self.viewport_rect());
+
+        let mut content_rect = match self.coordinate_system {
+            CoordinateSystem::VisibleRange(visible_range, scale_to_fit) => {
+                let m = self.visible_range_matrix(visible_range, scale_to_fit)?;
+                m.map_rect(visible_range.sorted()).0
+            }
+            CoordinateSystem::PixelPerfect { virtual_size, .. } => {
+                skia_safe::Rect::from(self.pixel_perfect_viewport(virtual_size)?)
+            }
+            _ => viewport_rect,
+        };
+
+        // The content may extend past the edges, i.e. if the window is smaller than the virtual
+        // size of a pixel perfect coordinate system
+        if !content_rect.intersect(viewport_rect) {
+            return Err(CoordinateSystemError::EmptyRange);
+        }
+
+        let bars = [
+            skia_safe::Rect::new(
+                viewport_rect.left,
+                viewport_rect.top,
+                viewport_rect.right,
+                content_rect.top,
+            ),
+            skia_safe::Rect::new(
+                viewport_rect.left,
+                content_rect.bottom,
+                viewport_rect.right,
+                viewport_rect.bottom,
+            ),
+            skia_safe::Rect::new(
+                viewport_rect.left,
+                content_rect.top,
+                content_rect.left,
+                content_rect.bottom,
+            ),
+            skia_safe::Rect::new(
+                content_rect.right,
+                content_rect.top,
+                viewport_rect.right,
+                content_rect.bottom,
+            ),
+        ]
+        .iter()
+        .filter(|bar| !bar.is_empty())
+        .cloned()
+        .collect();
+
+        Ok(Letterbox { content_rect, bars })
+    }
+
+    /// Returns the inverse of `coordinate_system_matrix`, which maps raw pixels on the surface to
+    /// canvas coordinates
+    pub fn inverse_coordinate_system_matrix(
+        &self,
+        coordinate_system: CoordinateSystem,
+    ) -> Result<skia_safe::Matrix, CoordinateSystemError> {
+        self.coordinate_system_matrix(coordinate_system)?
+            .invert()
+            .ok_or(CoordinateSystemError::NotInvertible)
+    }
+
+    /// Convert a position in raw pixels on the surface (i.e. a mouse position) to canvas
+    /// coordinates, using the coordinate system returned by `coordinate_system`
+    pub fn screen_to_canvas<P: Into<skia_safe::Point>>(
+        &self,
+        screen_position: P,
+    ) -> Result<skia_safe::Point, CoordinateSystemError> {
+        let inverse = self.inverse_coordinate_system_matrix(self.coordinate_system)?;
+        Ok(inverse.map_point(screen_position))
+    }
+
+    /// Convert a position in canvas coordinates to raw pixels on the surface, using the coordinate
+    /// system returned by `coordinate_system`
+    pub fn canvas_to_screen<P: Into<skia_safe::Point>>(
+        &self,
+        canvas_position: P,
+    ) -> Result<skia_safe::Point, CoordinateSystemError> {
+        let m = self.coordinate_system_matrix(self.coordinate_system)?;
+        Ok(m.map_point(canvas_position))
+    }
+
+    /// Returns the matrix used by `use_physical_coordinates`. This is the identity matrix unless the
+    /// helper was created with `viewport`.
+    pub fn physical_coordinates_matrix(&self) -> skia_safe::Matrix {
+        skia_safe::Matrix::translate((self.viewport_origin.x as f32, self.viewport_origin.y as f32))
+    }
+
+    /// Returns the matrix used by `use_logical_coordinates`
+    pub fn logical_coordinates_matrix(&self) -> skia_safe::Matrix {
+        // To handle hi-dpi displays, we need to compare the logical size of the window with the
+        // actual canvas size. Critically, the canvas size won't necessarily be the size of the
+        // window in physical pixels.
+        let scale = (
+            (f64::from(self.surface_extents.width) / self.window_logical_size.width as f64) as f32,
+            (f64::from(self.surface_extents.height) / self.window_logical_size.height as f64)
+                as f32,
+        );
+
+        let mut m = self.physical_coordinates_matrix();
+        m.pre_scale(scale, None);
+        m
+    }
+
+    /// Returns the matrix used by `use_centered_coordinates`
+    pub fn centered_coordinates_matrix(
+        &self,
+        y_up: bool,
+    ) -> skia_safe::Matrix {
+        let mut m = self.logical_coordinates_matrix();
+        m.pre_translate((
+            self.window_logical_size.width as f32 / 2.0,
+            self.window_logical_size.height as f32 / 2.0,
+        ));
+
+        if y_up {
+            m.pre_scale((1.0, -1.0), None);
+        }
+
+        m
+    }
+
+    /// Returns the matrix used by `use_bottom_left_y_up_coordinates`
+    pub fn bottom_left_y_up_coordinates_matrix(&self) -> skia_safe::Matrix {
+        let mut m = self.logical_coordinates_matrix();
+        m.pre_translate((0.0, self.window_logical_size.height as f32));
+        m.pre_scale((1.0, -1.0), None);
+        m
+    }
+
+    /// Returns the largest whole number scale at which the virtual canvas fits on the surface. If
+    /// the surface is smaller than the virtual canvas, this is 1 and the edges are cut off.
+    pub fn pixel_perfect_scale(
+        &self,
+        virtual_size: PhysicalSize,
+    ) -> Result<u32, CoordinateSystemError> {
+        if virtual_size.width == 0 || virtual_size.height == 0 {
+            return Err(CoordinateSystemError::EmptyRange);
+        }
+
+        let scale_x = self.surface_extents.width / virtual_size.width;
+        let scale_y = self.surface_extents.height / virtual_size.height;
+        Ok(scale_x.min(scale_y).max(1))
+    }
+
+    /// Returns the area of the surface, in raw pixels, that the scaled virtual canvas is drawn to
+    /// for `CoordinateSystem::PixelPerfect`. It is centered, and the rest of the surface is
+    /// letterboxed.
+    pub fn pixel_perfect_viewport(
+        &self,
+        virtual_size: PhysicalSize,
+    ) -> Result<skia_safe::IRect, CoordinateSystemError> {
+        let scale = self.pixel_perfect_scale(virtual_size)?;
+        let width = (virtual_size.width * scale) as i32;
+        let height = (virtual_size.height * scale) as i32;
+        let left = self.viewport_origin.x + (self.surface_extents.width as i32 - width) / 2;
+        let top = self.viewport_origin.y + (self.surface_extents.height as i32 - height) / 2;
+
+        Ok(skia_safe::IRect::from_xywh(left, top, width, height))
+    }
+
+    /// Returns the matrix that maps pixels of the virtual canvas to raw pixels on the surface for
+    /// `CoordinateSystem::PixelPerfect`
+    pub fn pixel_perfect_matrix(
+        &self,
+        virtual_size: PhysicalSize,
+    ) -> Result<skia_safe::Matrix, CoordinateSystemError> {
+        let scale = self.pixel_perfect_scale(virtual_size)? as f32;
+        let viewport = self.pixel_perfect_viewport(virtual_size)?;
+
+        let mut m = skia_safe::Matrix::translate((viewport.left as f32, viewport.top as f32));
+        m.pre_scale((scale, scale), None);
+        Ok(m)
+    }
+
+    /// Returns the matrix used by `use_visible_range`
+    pub fn visible_range_matrix(
+        &self,
         mut visible_range: skia_safe::Rect,
         scale_to_fit: skia_safe::matrix::ScaleToFit,
-    ) -> Result<(), ()> {
+    ) -> Result<skia_safe::Matrix, CoordinateSystemError> {
         let x_scale = if visible_range.left <= visible_range.right {
             1.0
         } else {
@@ -265,32 +791,28 @@ impl CoordinateSystemHelper {
             -1.0
         };
 
-        let dst = skia_safe::Rect {
-            left: 0.0,
-            top: 0.0,
-            right: self.surface_extents.width as f32,
-            bottom: self.surface_extents.height as f32,
-        };
+        let dst = skia_safe::Rect::from(self.viewport_rect());
 
-        let m = skia_safe::Matrix::from_rect_to_rect(visible_range, dst, scale_to_fit);
-        match m {
-            Some(m) => {
-                canvas.set_matrix(&m.into());
-                canvas.scale((x_scale, y_scale));
-                Ok(())
-            }
-            None => Err(()),
+        if dst.is_empty() {
+            return Err(CoordinateSystemError::EmptyRange);
         }
+
+        let mut m = skia_safe::Matrix::from_rect_to_rect(visible_range, dst, scale_to_fit)
+            .ok_or(CoordinateSystemError::EmptyRange)?;
+        m.pre_scale((x_scale, y_scale), None);
+        Ok(m)
     }
 
-    /// Given a center position and half-extents for X, calculate an appropriate Y half-extents that
-    /// is consistent with the aspect ratio.
-    pub fn use_fixed_width(
+    /// Returns the matrix used by `use_fixed_width`
+    pub fn fixed_width_matrix(
         &self,
-        canvas: &mut skia_safe::Canvas,
         center: skia_safe::Point,
         x_half_extents: f32,
-    ) -> Result<(), ()> {
+    ) -> Result<skia_safe::Matrix, CoordinateSystemError> {
+        if self.surface_extents.width == 0 || self.surface_extents.height == 0 {
+            return Err(CoordinateSystemError::EmptyRange);
+        }
+
         let left = center.x - x_half_extents;
         let right = center.x + x_half_extents;
         let y_half_extents = x_half_extents as f32
@@ -305,6 +827,249 @@ impl CoordinateSystemHelper {
             bottom,
         };
 
-        self.use_visible_range(canvas, rect, skia_safe::matrix::ScaleToFit::Fill)
+        self.visible_range_matrix(rect, skia_safe::matrix::ScaleToFit::Fill)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skia_safe::matrix::ScaleToFit;
+    use skia_safe::Point;
+    use skia_safe::Rect;
+
+    fn helper(
+        width: u32,
+        height: u32,
+        scale_factor: f64,
+    ) -> CoordinateSystemHelper {
+        CoordinateSystemHelper::new(RafxExtents2D { width, height }, scale_factor)
+    }
+
+    fn assert_near(
+        actual: Point,
+        expected: Point,
+    ) {
+        assert!(
+            (actual.x - expected.x).abs() < 1e-3 && (actual.y - expected.y).abs() < 1e-3,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    fn coordinate_systems() -> Vec<CoordinateSystem> {
+        vec![
+            CoordinateSystem::Logical,
+            CoordinateSystem::Physical,
+            CoordinateSystem::Centered,
+            CoordinateSystem::CenteredYUp,
+            CoordinateSystem::BottomLeftYUp,
+            CoordinateSystem::VisibleRange(Rect::new(0.0, 0.0, 100.0, 50.0), ScaleToFit::Fill),
+            CoordinateSystem::VisibleRange(Rect::new(-10.0, -5.0, 10.0, 5.0), ScaleToFit::Center),
+            // Flipped horizontally, vertically, and both
+            CoordinateSystem::VisibleRange(Rect::new(100.0, 0.0, 0.0, 50.0), ScaleToFit::Fill),
+            CoordinateSystem::VisibleRange(Rect::new(0.0, 50.0, 100.0, 0.0), ScaleToFit::Start),
+            CoordinateSystem::VisibleRange(Rect::new(10.0, 5.0, -10.0, -5.0), ScaleToFit::End),
+            CoordinateSystem::FixedWidth(Point::new(5.0, -5.0), 20.0),
+            CoordinateSystem::FixedWidth(Point::new(0.0, 0.0), -20.0),
+            CoordinateSystem::PixelPerfect {
+                virtual_size: PhysicalSize::new(160, 90),
+                letterbox_color: skia_safe::Color::BLACK,
+            },
+        ]
+    }
+
+    #[test]
+    fn inverse_round_trip() {
+        let screen_points = [
+            Point::new(0.0, 0.0),
+            Point::new(13.5, 7.25),
+            Point::new(400.0, 300.0),
+            Point::new(799.0, 599.0),
+        ];
+
+        for coordinate_system in coordinate_systems() {
+            let helper = helper(800, 600, 2.0).with_coordinate_system(coordinate_system);
+            for screen_point in &screen_points {
+                let canvas_point = helper.screen_to_canvas(*screen_point).unwrap();
+                assert_near(
+                    helper.canvas_to_screen(canvas_point).unwrap(),
+                    *screen_point,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn is_y_up_matches_matrix() {
+        let helper = helper(800, 600, 2.0);
+        for coordinate_system in coordinate_systems() {
+            let matrix = helper.coordinate_system_matrix(coordinate_system).unwrap();
+            assert_eq!(
+                coordinate_system.is_y_up(),
+                matrix.scale_y() < 0.0,
+                "{:?}",
+                coordinate_system
+            );
+        }
+    }
+
+    #[test]
+    fn inverse_round_trip_in_viewport() {
+        let viewport = helper(800, 600, 1.0)
+            .viewport(skia_safe::IRect::new(400, 0, 800, 300))
+            .unwrap();
+
+        for coordinate_system in coordinate_systems() {
+            let helper = viewport.clone().with_coordinate_system(coordinate_system);
+            let screen_point = Point::new(512.0, 100.0);
+            let canvas_point = helper.screen_to_canvas(screen_point).unwrap();
+            assert_near(helper.canvas_to_screen(canvas_point).unwrap(), screen_point);
+        }
+    }
+
+    #[test]
+    fn flipped_visible_range_maps_corners() {
+        // +Y up: the top of the range is the bottom of the canvas
+        let helper = helper(200, 100, 1.0).with_coordinate_system(CoordinateSystem::VisibleRange(
+            Rect::new(0.0, 10.0, 20.0, 0.0),
+            ScaleToFit::Fill,
+        ));
+
+        assert_near(
+            helper.canvas_to_screen((0.0, 10.0)).unwrap(),
+            Point::new(0.0, 0.0),
+        );
+        assert_near(
+            helper.canvas_to_screen((20.0, 0.0)).unwrap(),
+            Point::new(200.0, 100.0),
+        );
+        assert_near(
+            helper.screen_to_canvas((100.0, 25.0)).unwrap(),
+            Point::new(10.0, 7.5),
+        );
+    }
+
+    #[test]
+    fn unusable_coordinate_systems() {
+        let helper = helper(800, 600, 1.0);
+        assert_eq!(
+            helper
+                .coordinate_system_matrix(CoordinateSystem::None)
+                .err(),
+            Some(CoordinateSystemError::UnknownMatrix)
+        );
+        assert_eq!(
+            helper
+                .visible_range_matrix(Rect::new(5.0, 5.0, 5.0, 10.0), ScaleToFit::Fill)
+                .err(),
+            Some(CoordinateSystemError::EmptyRange)
+        );
+
+        let minimized = self::helper(0, 0, 1.0);
+        assert_eq!(
+            minimized
+                .coordinate_system_matrix(CoordinateSystem::FixedWidth(Point::new(0.0, 0.0), 1.0))
+                .err(),
+            Some(CoordinateSystemError::EmptyRange)
+        );
+    }
+
+    #[test]
+    fn center_letterbox_wide_surface() {
+        // A square range on a 2:1 surface leaves bars on the left and right
+        let letterbox = helper(200, 100, 1.0)
+            .with_coordinate_system(CoordinateSystem::VisibleRange(
+                Rect::new(0.0, 0.0, 100.0, 100.0),
+                ScaleToFit::Center,
+            ))
+            .letterbox()
+            .unwrap();
+
+        assert_eq!(letterbox.content_rect, Rect::new(50.0, 0.0, 150.0, 100.0));
+        assert_eq!(
+            letterbox.bars,
+            vec![
+                Rect::new(0.0, 0.0, 50.0, 100.0),
+                Rect::new(150.0, 0.0, 200.0, 100.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn center_letterbox_tall_surface() {
+        // A square range on a 1:2 surface leaves bars on the top and bottom
+        let letterbox = helper(100, 200, 1.0)
+            .with_coordinate_system(CoordinateSystem::VisibleRange(
+                Rect::new(0.0, 0.0, 100.0, 100.0),
+                ScaleToFit::Center,
+            ))
+            .letterbox()
+            .unwrap();
+
+        assert_eq!(letterbox.content_rect, Rect::new(0.0, 50.0, 100.0, 150.0));
+        assert_eq!(
+            letterbox.bars,
+            vec![
+                Rect::new(0.0, 0.0, 100.0, 50.0),
+                Rect::new(0.0, 150.0, 100.0, 200.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_has_no_letterbox() {
+        let letterbox = helper(200, 100, 1.0)
+            .with_coordinate_system(CoordinateSystem::VisibleRange(
+                Rect::new(0.0, 0.0, 100.0, 100.0),
+                ScaleToFit::Fill,
+            ))
+            .letterbox()
+            .unwrap();
+
+        assert_eq!(letterbox.content_rect, Rect::new(0.0, 0.0, 200.0, 100.0));
+        assert!(!letterbox.has_bars());
+    }
+
+    #[test]
+    fn pixel_perfect_scale() {
+        let virtual_size = PhysicalSize::new(160, 90);
+        let helper = helper(800, 600, 1.0);
+
+        assert_eq!(helper.pixel_perfect_scale(virtual_size), Ok(5));
+        assert_eq!(
+            helper.pixel_perfect_viewport(virtual_size),
+            Ok(skia_safe::IRect::from_xywh(0, 75, 800, 450))
+        );
+        assert_eq!(
+            helper.pixel_perfect_scale(PhysicalSize::new(0, 90)),
+            Err(CoordinateSystemError::EmptyRange)
+        );
+    }
+
+    #[test]
+    fn pixel_perfect_surface_smaller_than_virtual_size() {
+        // The scale never drops below 1, so the virtual canvas is centered and its edges are cut off
+        let virtual_size = PhysicalSize::new(160, 90);
+        let helper = helper(100, 50, 1.0).with_coordinate_system(CoordinateSystem::PixelPerfect {
+            virtual_size,
+            letterbox_color: skia_safe::Color::BLACK,
+        });
+
+        assert_eq!(helper.pixel_perfect_scale(virtual_size), Ok(1));
+        assert_eq!(
+            helper.pixel_perfect_viewport(virtual_size),
+            Ok(skia_safe::IRect::from_xywh(-30, -20, 160, 90))
+        );
+
+        let letterbox = helper.letterbox().unwrap();
+        assert_eq!(letterbox.content_rect, Rect::new(0.0, 0.0, 100.0, 50.0));
+        assert!(!letterbox.has_bars());
+
+        assert_near(
+            helper.screen_to_canvas((0.0, 0.0)).unwrap(),
+            Point::new(30.0, 20.0),
+        );
     }
 }
@@ -0,0 +1,114 @@
+use rafx::api::RafxError;
+use rafx::api::ash::vk;
+
+use super::CoordinateSystemError;
+
+/// Result type for operations that can fail with a `SkulpinError`
+pub type SkulpinResult<T> = Result<T, SkulpinError>;
+
+/// An error from creating or using the renderer
+#[derive(Debug, Clone)]
+pub enum SkulpinError {
+    /// The graphics API instance or device could not be created, i.e. the driver does not support
+    /// vulkan
+    DeviceCreation(RafxError),
+
+    /// Skia could not be set up to use the vulkan device
+    SkiaContextCreation,
+
+    /// Skia could not create a surface of the given size to draw to
+    SkiaSurfaceCreation { width: u32, height: u32 },
+
+    /// The swapchain could not be created or rebuilt
+    Swapchain(RafxError),
+
+    /// A shader could not be loaded
+    ShaderLoading(String),
+
+    /// The GPU device was lost, i.e. because the driver was updated or reset. Call
+    /// `Renderer::recreate` to recover.
+    DeviceLost,
+
+    /// The window's surface was lost. Call `Renderer::recreate` to recover.
+    SurfaceLost,
+
+    /// The coordinate system could not be applied to the canvas
+    CoordinateSystem(CoordinateSystemError),
+
+    /// Any other error reported by the graphics API while rendering
+    Rafx(RafxError),
+}
+
+impl std::error::Error for SkulpinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            SkulpinError::DeviceCreation(ref e) => Some(e),
+            SkulpinError::Swapchain(ref e) => Some(e),
+            SkulpinError::CoordinateSystem(ref e) => Some(e),
+            SkulpinError::Rafx(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for SkulpinError {
+    fn fmt(
+        &self,
+        fmt: &mut core::fmt::Formatter,
+    ) -> core::fmt::Result {
+        match *self {
+            SkulpinError::DeviceCreation(ref e) => {
+                write!(fmt, "Failed to create the graphics device: {}", e)
+            }
+            SkulpinError::SkiaContextCreation => {
+                write!(fmt, "Failed to create the skia context")
+            }
+            SkulpinError::SkiaSurfaceCreation { width, height } => write!(
+                fmt,
+                "Failed to create a skia surface of size {}x{}",
+                width, height
+            ),
+            SkulpinError::Swapchain(ref e) => write!(fmt, "Swapchain error: {}", e),
+            SkulpinError::ShaderLoading(ref e) => write!(fmt, "Failed to load shader: {}", e),
+            SkulpinError::DeviceLost => write!(fmt, "The graphics device was lost"),
+            SkulpinError::SurfaceLost => write!(fmt, "The window surface was lost"),
+            SkulpinError::CoordinateSystem(ref e) => e.fmt(fmt),
+            SkulpinError::Rafx(ref e) => e.fmt(fmt),
+        }
+    }
+}
+
+impl SkulpinError {
+    /// Returns true if the renderer can't be used anymore but may work again after calling
+    /// `Renderer::recreate`
+    pub fn requires_recreate(&self) -> bool {
+        matches!(self, SkulpinError::DeviceLost | SkulpinError::SurfaceLost)
+    }
+
+    // Used for errors from creating, acquiring, or presenting the swapchain
+    pub(crate) fn from_swapchain_error(error: RafxError) -> Self {
+        Self::from_lost_error(&error).unwrap_or(SkulpinError::Swapchain(error))
+    }
+
+    fn from_lost_error(error: &RafxError) -> Option<Self> {
+        match *error {
+            RafxError::VkError(vk::Result::ERROR_DEVICE_LOST) => Some(SkulpinError::DeviceLost),
+            RafxError::VkError(vk::Result::ERROR_SURFACE_LOST_KHR) => {
+                Some(SkulpinError::SurfaceLost)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<RafxError> for SkulpinError {
+    fn from(error: RafxError) -> Self {
+        Self::from_lost_error(&error).unwrap_or(SkulpinError::Rafx(error))
+    }
+}
+
+impl From<CoordinateSystemError> for SkulpinError {
+    fn from(error: CoordinateSystemError) -> Self {
+        SkulpinError::CoordinateSystem(error)
+    }
+}
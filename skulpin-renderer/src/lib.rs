@@ -5,7 +5,22 @@ pub use rafx;
 pub use skia_safe;
 pub use skia_bindings;
 
-pub const MAX_FRAMES_IN_FLIGHT: usize = 2;
+/// The most frames that can be queued for the GPU at once. This matches rafx's per-frame resource
+/// recycling and is the default for `RendererBuilder::max_frames_in_flight`
+pub const MAX_FRAMES_IN_FLIGHT: usize = rafx::framework::MAX_FRAMES_IN_FLIGHT;
+
+mod error;
+pub use error::SkulpinError;
+pub use error::SkulpinResult;
+
+mod adapter;
+pub use adapter::AdapterType;
+pub use adapter::AdapterInfo;
+
+mod present_mode;
+pub use present_mode::PresentMode;
+
+mod surface_support;
 
 mod skia_support;
 pub use skia_support::VkSkiaContext;
@@ -22,3 +37,13 @@ pub use coordinates::LogicalSize;
 pub use coordinates::PhysicalSize;
 pub use coordinates::CoordinateSystem;
 pub use coordinates::CoordinateSystemHelper;
+pub use coordinates::CoordinateSystemError;
+pub use coordinates::Letterbox;
+
+mod camera;
+pub use camera::Camera2D;
+
+mod upright;
+pub use upright::is_canvas_mirrored;
+pub use upright::draw_str_upright;
+pub use upright::draw_image_upright;
@@ -0,0 +1,162 @@
+use rafx::api::ash::vk;
+
+/// Controls how finished frames are queued for display. Lower latency modes may cause tearing or
+/// use more power. Not every mode is supported by every platform, so the renderer takes a list of
+/// modes in order of preference. `Fifo` is always supported.
+///
+/// The graphics backend only allows enabling or disabling vsync, which selects the first supported
+/// mode from `VSYNC_ON` or `VSYNC_OFF`. A preferred mode can only be used if it's the one that
+/// setting selects, i.e. `Immediate` can't be used if `Mailbox` is also supported.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PresentMode {
+    /// Frames are shown as soon as they are presented without waiting for the vertical blank. This
+    /// has the lowest latency but may tear.
+    Immediate,
+
+    /// Waits for the vertical blank, but a newly presented frame replaces the one that is waiting
+    /// to be shown instead of blocking. Low latency without tearing.
+    Mailbox,
+
+    /// Waits for the vertical blank, and presenting blocks when the queue is full. This is
+    /// traditional vsync.
+    Fifo,
+
+    /// Like `Fifo`, but a frame that misses the vertical blank is shown immediately, which may
+    /// tear.
+    FifoRelaxed,
+}
+
+impl PresentMode {
+    /// The modes used when vsync is enabled
+    pub const VSYNC_ON: [PresentMode; 1] = [PresentMode::Fifo];
+
+    /// The modes used when vsync is disabled, in order of preference
+    pub const VSYNC_OFF: [PresentMode; 4] = [
+        PresentMode::Mailbox,
+        PresentMode::FifoRelaxed,
+        PresentMode::Immediate,
+        PresentMode::Fifo,
+    ];
+
+    pub(crate) fn from_vk(present_mode: vk::PresentModeKHR) -> Option<Self> {
+        match present_mode {
+            vk::PresentModeKHR::IMMEDIATE => Some(PresentMode::Immediate),
+            vk::PresentModeKHR::MAILBOX => Some(PresentMode::Mailbox),
+            vk::PresentModeKHR::FIFO => Some(PresentMode::Fifo),
+            vk::PresentModeKHR::FIFO_RELAXED => Some(PresentMode::FifoRelaxed),
+            _ => None,
+        }
+    }
+
+    /// The modes that are tried for the given vsync setting, in order of preference
+    pub fn priority(vsync_enabled: bool) -> &'static [PresentMode] {
+        if vsync_enabled {
+            &Self::VSYNC_ON
+        } else {
+            &Self::VSYNC_OFF
+        }
+    }
+
+    /// Returns the mode that is used for the given vsync setting on a surface that supports the
+    /// `available` modes. This falls back to `Fifo`, which every surface supports.
+    pub fn choose(
+        vsync_enabled: bool,
+        available: &[PresentMode],
+    ) -> PresentMode {
+        Self::priority(vsync_enabled)
+            .iter()
+            .copied()
+            .find(|x| available.contains(x))
+            .unwrap_or(PresentMode::Fifo)
+    }
+
+    /// Returns the vsync setting to pass to the graphics backend and the mode it will use, for the
+    /// first of the `preferred` modes that can be used on a surface that supports the `available`
+    /// modes. If none of them can be used, vsync is enabled and `Fifo` is used.
+    pub fn resolve(
+        preferred: &[PresentMode],
+        available: &[PresentMode],
+    ) -> (bool, PresentMode) {
+        // These are the only two modes the backend can end up using
+        let vsync_off_mode = Self::choose(false, available);
+        let vsync_on_mode = Self::choose(true, available);
+
+        for present_mode in preferred {
+            if *present_mode == vsync_off_mode {
+                return (false, vsync_off_mode);
+            } else if *present_mode == vsync_on_mode {
+                return (true, vsync_on_mode);
+            }
+        }
+
+        (true, vsync_on_mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vsync_on_uses_fifo() {
+        let all = PresentMode::VSYNC_OFF;
+        assert_eq!(PresentMode::choose(true, &all), PresentMode::Fifo);
+        assert_eq!(PresentMode::choose(true, &[]), PresentMode::Fifo);
+    }
+
+    #[test]
+    fn vsync_off_fallback_order() {
+        use PresentMode::*;
+
+        let mut available = vec![Fifo, Immediate, FifoRelaxed, Mailbox];
+        assert_eq!(PresentMode::choose(false, &available), Mailbox);
+
+        available.retain(|x| *x != Mailbox);
+        assert_eq!(PresentMode::choose(false, &available), FifoRelaxed);
+
+        available.retain(|x| *x != FifoRelaxed);
+        assert_eq!(PresentMode::choose(false, &available), Immediate);
+
+        available.retain(|x| *x != Immediate);
+        assert_eq!(PresentMode::choose(false, &available), Fifo);
+    }
+
+    #[test]
+    fn falls_back_to_fifo() {
+        assert_eq!(PresentMode::choose(false, &[]), PresentMode::Fifo);
+    }
+
+    #[test]
+    fn resolve_uses_first_reachable_preference() {
+        use PresentMode::*;
+
+        let all = [Fifo, Immediate, FifoRelaxed, Mailbox];
+        assert_eq!(
+            PresentMode::resolve(&[Mailbox, Fifo], &all),
+            (false, Mailbox)
+        );
+        assert_eq!(PresentMode::resolve(&[Fifo, Mailbox], &all), (true, Fifo));
+
+        // Immediate is supported, but disabling vsync selects Mailbox
+        assert_eq!(PresentMode::resolve(&[Immediate, Fifo], &all), (true, Fifo));
+        assert_eq!(
+            PresentMode::resolve(&[Immediate, Mailbox], &all),
+            (false, Mailbox)
+        );
+
+        // Without Mailbox, disabling vsync selects Immediate
+        let no_mailbox = [Fifo, Immediate];
+        assert_eq!(
+            PresentMode::resolve(&[Mailbox, Immediate], &no_mailbox),
+            (false, Immediate)
+        );
+    }
+
+    #[test]
+    fn resolve_falls_back_to_fifo() {
+        use PresentMode::*;
+
+        assert_eq!(PresentMode::resolve(&[], &[Fifo, Mailbox]), (true, Fifo));
+        assert_eq!(PresentMode::resolve(&[Mailbox], &[Fifo]), (true, Fifo));
+    }
+}
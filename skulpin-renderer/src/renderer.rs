@@ -4,10 +4,18 @@ use rafx::framework::*;
 
 use super::CoordinateSystemHelper;
 use super::CoordinateSystem;
+use super::PhysicalSize;
 use rafx::api::raw_window_handle::HasRawWindowHandle;
 use std::sync::Arc;
 use crate::VkSkiaContext;
 use crate::skia_support::VkSkiaSurface;
+use crate::SkulpinError;
+use crate::SkulpinResult;
+use crate::AdapterInfo;
+use crate::adapter;
+use crate::PresentMode;
+use crate::surface_support::SurfaceSupport;
+use crate::MAX_FRAMES_IN_FLIGHT;
 
 use rafx::api::RafxValidationMode;
 
@@ -44,10 +52,13 @@ impl Into<RafxValidationMode> for ValidationMode {
 
 /// A builder to create the renderer. It's easier to use AppBuilder and implement an AppHandler, but
 /// initializing the renderer and maintaining the window yourself allows for more customization
-#[derive(Default)]
+///
+/// Transparent windows are not supported. rafx always creates the swapchain with opaque composite
+/// alpha, so the canvas's alpha never reaches the compositor.
 pub struct RendererBuilder {
     coordinate_system: CoordinateSystem,
-    vsync_enabled: bool,
+    present_modes: Vec<PresentMode>,
+    max_frames_in_flight: usize,
     validation_mode: ValidationMode,
 }
 
@@ -56,7 +67,8 @@ impl RendererBuilder {
     pub fn new() -> Self {
         RendererBuilder {
             coordinate_system: Default::default(),
-            vsync_enabled: true,
+            present_modes: PresentMode::VSYNC_ON.to_vec(),
+            max_frames_in_flight: MAX_FRAMES_IN_FLIGHT,
             validation_mode: ValidationMode::default(),
         }
     }
@@ -71,11 +83,39 @@ impl RendererBuilder {
         self
     }
 
+    /// Enabling vsync uses `PresentMode::VSYNC_ON`, disabling it tries the modes in
+    /// `PresentMode::VSYNC_OFF` in order. Defaults to true. This replaces the modes set with
+    /// `present_modes`.
     pub fn vsync_enabled(
         mut self,
         vsync_enabled: bool,
     ) -> Self {
-        self.vsync_enabled = vsync_enabled;
+        self.present_modes = PresentMode::priority(vsync_enabled).to_vec();
+        self
+    }
+
+    /// The present modes to try, in order of preference. The first one that the window supports
+    /// and the graphics backend can select is used (see `PresentMode::resolve`), falling back to
+    /// `Fifo`. Use `Renderer::present_mode` to see which one was chosen.
+    pub fn present_modes(
+        mut self,
+        present_modes: &[PresentMode],
+    ) -> Self {
+        self.present_modes = present_modes.to_vec();
+        self
+    }
+
+    /// Limit how many frames the CPU can queue ahead of the GPU. 1 has the lowest latency but
+    /// the CPU and GPU can't work at the same time. This is clamped between 1 and
+    /// `MAX_FRAMES_IN_FLIGHT` (the default), and the swapchain image count is also a limit.
+    ///
+    /// The swapchain image count itself can't be configured, as the graphics backend always uses
+    /// one more than the minimum the platform requires.
+    pub fn max_frames_in_flight(
+        mut self,
+        max_frames_in_flight: usize,
+    ) -> Self {
+        self.max_frames_in_flight = max_frames_in_flight;
         self
     }
 
@@ -92,20 +132,58 @@ impl RendererBuilder {
         self,
         window: &dyn HasRawWindowHandle,
         window_size: RafxExtents2D,
-    ) -> RafxResult<Renderer> {
+    ) -> SkulpinResult<Renderer> {
         Renderer::new(
             window,
             window_size,
             self.coordinate_system,
-            self.vsync_enabled,
+            &self.present_modes,
+            self.max_frames_in_flight,
             self.validation_mode,
         )
     }
 }
+
+impl Default for RendererBuilder {
+    fn default() -> Self {
+        RendererBuilder::new()
+    }
+}
+
 struct SwapchainEventListener<'a> {
     skia_context: &'a mut VkSkiaContext,
     skia_surface: &'a mut Option<VkSkiaSurface>,
     resource_manager: &'a ResourceManager,
+    // Set when the skia surface can't be created. The listener can only return a RafxError, which
+    // rafx passes through unchanged from the call that invoked the listener. That RafxError is only
+    // a placeholder, so every error from such a call must go through take_error to get this back.
+    error: Option<SkulpinError>,
+}
+
+impl<'a> SwapchainEventListener<'a> {
+    fn new(
+        skia_context: &'a mut VkSkiaContext,
+        skia_surface: &'a mut Option<VkSkiaSurface>,
+        resource_manager: &'a ResourceManager,
+    ) -> Self {
+        SwapchainEventListener {
+            skia_context,
+            skia_surface,
+            resource_manager,
+            error: None,
+        }
+    }
+
+    // Returns the error from creating the skia surface if there was one, otherwise the swapchain
+    // error
+    fn take_error(
+        &mut self,
+        error: RafxError,
+    ) -> SkulpinError {
+        self.error
+            .take()
+            .unwrap_or_else(|| SkulpinError::from_swapchain_error(error))
+    }
 }
 
 impl<'a> RafxSwapchainEventListener for SwapchainEventListener<'a> {
@@ -114,16 +192,26 @@ impl<'a> RafxSwapchainEventListener for SwapchainEventListener<'a> {
         _device_context: &RafxDeviceContext,
         swapchain: &RafxSwapchain,
     ) -> RafxResult<()> {
-        *self.skia_surface = Some(VkSkiaSurface::new(
+        let skia_surface = VkSkiaSurface::new(
             &self.resource_manager,
             &mut self.skia_context,
             RafxExtents2D {
                 width: swapchain.swapchain_def().width.max(1),
                 height: swapchain.swapchain_def().height.max(1),
             },
-        )?);
+        );
 
-        Ok(())
+        match skia_surface {
+            Ok(skia_surface) => {
+                *self.skia_surface = Some(skia_surface);
+                Ok(())
+            }
+            Err(e) => {
+                let rafx_error = RafxError::StringError(e.to_string());
+                self.error = Some(e);
+                Err(rafx_error)
+            }
+        }
     }
 
     fn swapchain_destroyed(
@@ -142,6 +230,16 @@ impl<'a> RafxSwapchainEventListener for SwapchainEventListener<'a> {
 pub struct Renderer {
     // Ordered in drop order
     pub coordinate_system: CoordinateSystem,
+    // The preferred present modes, and the mode that was chosen from them
+    present_modes: Vec<PresentMode>,
+    present_mode: PresentMode,
+    vsync_enabled: bool,
+    max_frames_in_flight: usize,
+    validation_mode: ValidationMode,
+    // Set when the device or surface is lost, until the renderer is recreated
+    lost_error: Option<SkulpinError>,
+    device_info: AdapterInfo,
+    pub pixel_perfect_surface: Option<skia_safe::Surface>,
     pub skia_surface: Option<VkSkiaSurface>,
     pub skia_context: VkSkiaContext,
     pub skia_material_pass: MaterialPass,
@@ -164,43 +262,75 @@ impl Renderer {
         window: &dyn HasRawWindowHandle,
         window_size: RafxExtents2D,
         coordinate_system: CoordinateSystem,
-        vsync_enabled: bool,
+        present_modes: &[PresentMode],
+        max_frames_in_flight: usize,
         validation_mode: ValidationMode,
-    ) -> RafxResult<Renderer> {
+    ) -> SkulpinResult<Renderer> {
         let api_def = RafxApiDefVulkan {
             validation_mode: validation_mode.into(),
             ..Default::default()
         };
 
-        let api = unsafe { RafxApi::new_vulkan(window, &Default::default(), &api_def) }?;
+        let api = unsafe { RafxApi::new_vulkan(window, &Default::default(), &api_def) }
+            .map_err(SkulpinError::DeviceCreation)?;
         let device_context = api.device_context();
 
+        let device_info = AdapterInfo::from_device_context(&device_context)?;
+        info!("Using adapter {}", device_info);
+
         let resource_manager =
             rafx::framework::ResourceManager::new(&device_context, &RENDER_REGISTRY);
 
-        let swapchain = device_context.create_swapchain(
-            window,
-            &RafxSwapchainDef {
-                width: window_size.width,
-                height: window_size.height,
-                enable_vsync: vsync_enabled,
-            },
-        )?;
+        // This must happen before the swapchain is created, since it uses a temporary surface
+        let surface_support = SurfaceSupport::query(&device_context, window)?;
+        debug!(
+            "Available present modes: {:?}",
+            surface_support.present_modes
+        );
+
+        let (vsync_enabled, present_mode) =
+            PresentMode::resolve(present_modes, &surface_support.present_modes);
+        if !present_modes.contains(&present_mode) {
+            warn!(
+                "None of the preferred present modes {:?} can be used, using {:?}",
+                present_modes, present_mode
+            );
+        }
+        info!("Present mode: {:?}", present_mode);
+
+        let swapchain = device_context
+            .create_swapchain(
+                window,
+                &RafxSwapchainDef {
+                    width: window_size.width,
+                    height: window_size.height,
+                    enable_vsync: vsync_enabled,
+                },
+            )
+            .map_err(SkulpinError::from_swapchain_error)?;
 
-        let graphics_queue = device_context.create_queue(RafxQueueType::Graphics)?;
+        let graphics_queue = device_context
+            .create_queue(RafxQueueType::Graphics)
+            .map_err(SkulpinError::DeviceCreation)?;
 
-        let mut skia_context = VkSkiaContext::new(&device_context, &graphics_queue);
+        let mut skia_context = VkSkiaContext::new(&device_context, &graphics_queue)?;
         let mut skia_surface = None;
 
+        let mut swapchain_event_listener =
+            SwapchainEventListener::new(&mut skia_context, &mut skia_surface, &resource_manager);
         let swapchain_helper = RafxSwapchainHelper::new(
             &device_context,
             swapchain,
-            Some(&mut SwapchainEventListener {
-                skia_context: &mut skia_context,
-                skia_surface: &mut skia_surface,
-                resource_manager: &resource_manager,
-            }),
-        )?;
+            Some(&mut swapchain_event_listener),
+        )
+        .map_err(|e| swapchain_event_listener.take_error(e))?;
+
+        let max_frames_in_flight = max_frames_in_flight.max(1).min(MAX_FRAMES_IN_FLIGHT);
+        info!(
+            "Swapchain image count: {}, max frames in flight: {}",
+            swapchain_helper.image_count(),
+            max_frames_in_flight
+        );
 
         let resource_context = resource_manager.resource_context();
 
@@ -222,31 +352,217 @@ impl Renderer {
             graphics_queue,
             skia_material_pass,
             coordinate_system,
+            present_modes: present_modes.to_vec(),
+            present_mode,
+            vsync_enabled,
+            max_frames_in_flight,
+            validation_mode,
+            lost_error: None,
+            device_info,
+            pixel_perfect_surface: None,
             skia_context,
             skia_surface,
         })
     }
 
+    /// Describes the GPU that is being rendered with. This is useful to include in bug reports.
+    pub fn device_info(&self) -> &AdapterInfo {
+        &self.device_info
+    }
+
+    /// Lists the adapters visible to the vulkan instance the renderer uses, including the one
+    /// returned by `device_info`.
+    ///
+    /// The adapter can't be chosen. rafx picks it, preferring discrete GPUs over integrated GPUs,
+    /// and does not provide a way to override its choice. The vulkan loader can be restricted to
+    /// a particular driver (i.e. lavapipe) with the `VK_ICD_FILENAMES` environment variable.
+    pub fn adapters(&self) -> SkulpinResult<Vec<AdapterInfo>> {
+        adapter::enumerate_adapters(&self.api.device_context())
+    }
+
+    /// Whether vsync was enabled in the graphics backend to select `present_mode`
+    pub fn vsync_enabled(&self) -> bool {
+        self.vsync_enabled
+    }
+
+    /// The present mode the swapchain uses, chosen from the modes passed to
+    /// `RendererBuilder::present_modes`
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    /// The number of images in the swapchain. This is chosen by the graphics backend and is
+    /// usually one more than the minimum the platform requires.
+    pub fn swapchain_image_count(&self) -> usize {
+        self.swapchain_helper.image_count()
+    }
+
+    /// The number of frames the CPU can queue ahead of the GPU
+    pub fn max_frames_in_flight(&self) -> usize {
+        self.max_frames_in_flight
+    }
+
+    /// Tear down and create the device, skia context, and swapchain again with the same settings.
+    /// Call this after `draw` returns an error for which `SkulpinError::requires_recreate` is true.
+    /// Images and surfaces created with the old skia context can't be used anymore and must be
+    /// created again.
+    ///
+    /// If this fails, the renderer can't be used and `draw` will keep returning the error.
+    pub fn recreate(
+        &mut self,
+        window: &dyn HasRawWindowHandle,
+        window_size: RafxExtents2D,
+    ) -> SkulpinResult<()> {
+        info!("Recreating the renderer");
+
+        // The window can only have one swapchain, so release it before creating the new one
+        self.pixel_perfect_surface = None;
+        self.skia_surface = None;
+        self.destroy_swapchain();
+
+        // Stop skia from using the old device, which may be lost
+        self.skia_context.context.abandon();
+
+        let renderer = Renderer::new(
+            window,
+            window_size,
+            self.coordinate_system,
+            &self.present_modes,
+            self.max_frames_in_flight,
+            self.validation_mode,
+        );
+
+        match renderer {
+            Ok(renderer) => {
+                *self = renderer;
+                Ok(())
+            }
+            Err(e) => {
+                self.lost_error = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
     /// Call to render a frame. This can block for certain presentation modes. This will rebuild
     /// the swapchain if necessary.
+    ///
+    /// If the device or surface is lost, an error for which `SkulpinError::requires_recreate` is
+    /// true is returned, and will keep being returned until `recreate` is called.
     pub fn draw<F: FnOnce(&mut skia_safe::Canvas, CoordinateSystemHelper)>(
         &mut self,
         window_size: RafxExtents2D,
         scale_factor: f64,
         f: F,
-    ) -> RafxResult<()> {
+    ) -> SkulpinResult<()> {
+        if let Some(lost_error) = &self.lost_error {
+            return Err(lost_error.clone());
+        }
+
+        let result = self.draw_frame(window_size, scale_factor, f);
+        if let Err(e) = &result {
+            if e.requires_recreate() {
+                warn!("The renderer must be recreated: {}", e);
+                self.lost_error = Some(e.clone());
+            }
+        }
+
+        result
+    }
+
+    fn draw_frame<F: FnOnce(&mut skia_safe::Canvas, CoordinateSystemHelper)>(
+        &mut self,
+        window_size: RafxExtents2D,
+        scale_factor: f64,
+        f: F,
+    ) -> SkulpinResult<()> {
+        // There is nothing to draw to while the window is minimized
+        if window_size.width == 0 || window_size.height == 0 {
+            return Ok(());
+        }
+
+        // Check everything that depends on the window size before acquiring an image, because an
+        // acquired image must be presented even if drawing fails
+        let coordinate_system_helper = CoordinateSystemHelper::new(window_size, scale_factor)
+            .with_coordinate_system(self.coordinate_system);
+
+        let coordinate_system_matrix = match self.coordinate_system {
+            CoordinateSystem::None => None,
+            CoordinateSystem::PixelPerfect { virtual_size, .. } => {
+                Self::get_or_create_pixel_perfect_surface(
+                    &mut self.skia_context,
+                    &mut self.pixel_perfect_surface,
+                    virtual_size,
+                )?;
+                None
+            }
+            coordinate_system => {
+                Some(coordinate_system_helper.coordinate_system_matrix(coordinate_system)?)
+            }
+        };
+
         //
         // Begin the frame
         //
-        let frame = self.swapchain_helper.acquire_next_image(
-            window_size.width,
-            window_size.height,
-            Some(&mut SwapchainEventListener {
-                skia_context: &mut self.skia_context,
-                skia_surface: &mut self.skia_surface,
-                resource_manager: &self.resource_manager,
-            }),
-        )?;
+        let mut swapchain_event_listener = SwapchainEventListener::new(
+            &mut self.skia_context,
+            &mut self.skia_surface,
+            &self.resource_manager,
+        );
+        let frame = self
+            .swapchain_helper
+            .acquire_next_image(
+                window_size.width,
+                window_size.height,
+                Some(&mut swapchain_event_listener),
+            )
+            .map_err(|e| swapchain_event_listener.take_error(e))?;
+
+        match self.record_frame(
+            &frame,
+            coordinate_system_helper,
+            coordinate_system_matrix,
+            f,
+        ) {
+            Ok((_command_pool, command_buffer)) => {
+                frame
+                    .present(&self.graphics_queue, &[&command_buffer])
+                    .map_err(SkulpinError::from_swapchain_error)?;
+                Ok(())
+            }
+            Err(e) => {
+                // Dropping the frame without presenting it would leave the swapchain waiting on it.
+                // rafx would return the error passed here again from the next acquire_next_image,
+                // so it's received right away. The original error is only returned here.
+                frame.present_with_error(
+                    &self.graphics_queue,
+                    RafxError::StringError(e.to_string()),
+                );
+                let _ = self.swapchain_helper.wait_until_previous_frame_submitted();
+                Err(e)
+            }
+        }
+    }
+
+    // Draws the frame and records the commands that copy it to the swapchain image. The command
+    // pool is returned along with the command buffer so that it is not released before submission.
+    fn record_frame<F: FnOnce(&mut skia_safe::Canvas, CoordinateSystemHelper)>(
+        &mut self,
+        frame: &RafxPresentableFrame,
+        coordinate_system_helper: CoordinateSystemHelper,
+        coordinate_system_matrix: Option<skia_safe::Matrix>,
+        f: F,
+    ) -> SkulpinResult<(DynCommandPool, DynCommandBuffer)> {
+        // The swapchain helper allows a frame per swapchain image to be in flight. Wait for older
+        // frames to bring that down to the limit.
+        let image_count = self.swapchain_helper.image_count();
+        if self.max_frames_in_flight < image_count {
+            let sync_frame_index = (frame.rotating_frame_index() + image_count
+                - self.max_frames_in_flight)
+                % image_count;
+            self.swapchain_helper
+                .wait_until_sync_frame_idle(sync_frame_index)?;
+        }
 
         // Acquiring an image means a prior frame completely finished processing
         self.resource_manager.on_frame_complete()?;
@@ -254,27 +570,20 @@ impl Renderer {
         //
         // Do skia drawing (including the user's callback)
         //
-        let mut canvas = self.skia_surface.as_mut().unwrap().surface.canvas();
-
-        let coordinate_system_helper = CoordinateSystemHelper::new(window_size, scale_factor);
-
-        match self.coordinate_system {
-            CoordinateSystem::None => {}
-            CoordinateSystem::Physical => {
-                coordinate_system_helper.use_physical_coordinates(&mut canvas)
+        if let CoordinateSystem::PixelPerfect {
+            virtual_size,
+            letterbox_color,
+        } = self.coordinate_system
+        {
+            self.draw_pixel_perfect(virtual_size, letterbox_color, coordinate_system_helper, f)?;
+        } else {
+            let canvas = self.skia_surface.as_mut().unwrap().surface.canvas();
+            if let Some(coordinate_system_matrix) = coordinate_system_matrix {
+                canvas.set_matrix(&coordinate_system_matrix.into());
             }
-            CoordinateSystem::Logical => {
-                coordinate_system_helper.use_logical_coordinates(&mut canvas)
-            }
-            CoordinateSystem::VisibleRange(range, scale_to_fit) => coordinate_system_helper
-                .use_visible_range(&mut canvas, range, scale_to_fit)
-                .unwrap(),
-            CoordinateSystem::FixedWidth(center, x_half_extents) => coordinate_system_helper
-                .use_fixed_width(&mut canvas, center, x_half_extents)
-                .unwrap(),
+            f(canvas, coordinate_system_helper);
         }
 
-        f(&mut canvas, coordinate_system_helper);
         self.skia_context.context.flush_and_submit();
 
         //
@@ -427,39 +736,131 @@ impl Renderer {
 
         command_buffer.end()?;
 
-        frame.present(&self.graphics_queue, &[&command_buffer])?;
+        Ok((command_pool, command_buffer))
+    }
+
+    // rafx panics if the swapchain helper fails to destroy the swapchain when it is dropped, which
+    // happens if the device was lost. Each failed attempt gets further, so retry here to make the
+    // destroy in its drop a no-op.
+    fn destroy_swapchain(&mut self) {
+        const MAX_ATTEMPTS: usize = 3;
+        for _ in 0..MAX_ATTEMPTS {
+            match self.swapchain_helper.destroy(None) {
+                Ok(()) => return,
+                Err(e) => warn!("Failed to destroy the swapchain: {}", e),
+            }
+        }
+    }
+
+    // Draws to an offscreen surface of the virtual size, then scales it onto the swapchain surface
+    // by a whole number without filtering and fills the remaining area with the letterbox color
+    fn draw_pixel_perfect<F: FnOnce(&mut skia_safe::Canvas, CoordinateSystemHelper)>(
+        &mut self,
+        virtual_size: PhysicalSize,
+        letterbox_color: skia_safe::Color,
+        coordinate_system_helper: CoordinateSystemHelper,
+        f: F,
+    ) -> SkulpinResult<()> {
+        let viewport = coordinate_system_helper.pixel_perfect_viewport(virtual_size);
+
+        let pixel_perfect_surface = Self::get_or_create_pixel_perfect_surface(
+            &mut self.skia_context,
+            &mut self.pixel_perfect_surface,
+            virtual_size,
+        )?;
+
+        // The virtual canvas is drawn with an identity matrix and scaled afterwards, so the callback
+        // gets a helper for the virtual canvas rather than the window
+        let virtual_helper = CoordinateSystemHelper::new(
+            RafxExtents2D {
+                width: virtual_size.width,
+                height: virtual_size.height,
+            },
+            1.0,
+        )
+        .with_coordinate_system(CoordinateSystem::Physical);
+
+        let canvas = pixel_perfect_surface.canvas();
+        canvas.reset_matrix();
+        f(canvas, virtual_helper);
+
+        let image = pixel_perfect_surface.image_snapshot();
+
+        let canvas = self.skia_surface.as_mut().unwrap().surface.canvas();
+        canvas.reset_matrix();
+        canvas.clear(letterbox_color);
+
+        if let Ok(viewport) = viewport {
+            canvas.draw_image_rect_with_sampling_options(
+                &image,
+                None,
+                skia_safe::Rect::from(viewport),
+                skia_safe::SamplingOptions::new(
+                    skia_safe::FilterMode::Nearest,
+                    skia_safe::MipmapMode::None,
+                ),
+                &skia_safe::Paint::default(),
+            );
+        }
 
         Ok(())
     }
 
+    fn get_or_create_pixel_perfect_surface<'a>(
+        skia_context: &mut VkSkiaContext,
+        pixel_perfect_surface: &'a mut Option<skia_safe::Surface>,
+        virtual_size: PhysicalSize,
+    ) -> SkulpinResult<&'a mut skia_safe::Surface> {
+        let width = virtual_size.width.max(1) as i32;
+        let height = virtual_size.height.max(1) as i32;
+
+        let size_changed = pixel_perfect_surface
+            .as_ref()
+            .map(|surface| surface.width() != width || surface.height() != height)
+            .unwrap_or(true);
+
+        if size_changed {
+            debug!("Creating pixel perfect surface {}x{}", width, height);
+
+            // Match the format of the swapchain surface
+            let image_info = skia_safe::ImageInfo::new(
+                (width, height),
+                skia_safe::ColorType::N32,
+                skia_safe::AlphaType::Premul,
+                Some(skia_safe::ColorSpace::new_srgb_linear()),
+            );
+
+            let surface = skia_safe::Surface::new_render_target(
+                &mut skia_context.context,
+                skia_safe::Budgeted::Yes,
+                &image_info,
+                None,
+                skia_safe::gpu::SurfaceOrigin::TopLeft,
+                None,
+                false,
+            )
+            .ok_or(SkulpinError::SkiaSurfaceCreation {
+                width: width as u32,
+                height: height as u32,
+            })?;
+
+            *pixel_perfect_surface = Some(surface);
+        }
+
+        Ok(pixel_perfect_surface.as_mut().unwrap())
+    }
+
     fn load_material_pass(
         resource_context: &ResourceContext,
         cooked_vertex_shader_bytes: &[u8],
         cooked_fragment_shader_bytes: &[u8],
         fixed_function_state: FixedFunctionState,
-    ) -> RafxResult<MaterialPass> {
-        let cooked_vertex_shader_stage =
-            bincode::deserialize::<CookedShaderPackage>(cooked_vertex_shader_bytes)
-                .map_err(|x| format!("Failed to deserialize cooked shader: {:?}", x))?;
-        let vertex_shader_module = resource_context
-            .resources()
-            .get_or_create_shader_module_from_cooked_package(&cooked_vertex_shader_stage)?;
-        let vertex_entry_point = cooked_vertex_shader_stage
-            .find_entry_point("main")
-            .unwrap()
-            .clone();
+    ) -> SkulpinResult<MaterialPass> {
+        let (vertex_shader_module, vertex_entry_point) =
+            Self::load_shader_module(resource_context, cooked_vertex_shader_bytes)?;
 
-        // Create the fragment shader module and find the entry point
-        let cooked_fragment_shader_stage =
-            bincode::deserialize::<CookedShaderPackage>(cooked_fragment_shader_bytes)
-                .map_err(|x| format!("Failed to deserialize cooked shader: {:?}", x))?;
-        let fragment_shader_module = resource_context
-            .resources()
-            .get_or_create_shader_module_from_cooked_package(&cooked_fragment_shader_stage)?;
-        let fragment_entry_point = cooked_fragment_shader_stage
-            .find_entry_point("main")
-            .unwrap()
-            .clone();
+        let (fragment_shader_module, fragment_entry_point) =
+            Self::load_shader_module(resource_context, cooked_fragment_shader_bytes)?;
 
         let fixed_function_state = Arc::new(fixed_function_state);
 
@@ -468,16 +869,48 @@ impl Renderer {
             fixed_function_state,
             vec![vertex_shader_module, fragment_shader_module],
             &[&vertex_entry_point, &fragment_entry_point],
-        )?;
+        )
+        .map_err(|e| SkulpinError::ShaderLoading(e.to_string()))?;
 
         Ok(material_pass)
     }
+
+    // Create the shader module and find the entry point
+    fn load_shader_module(
+        resource_context: &ResourceContext,
+        cooked_shader_bytes: &[u8],
+    ) -> SkulpinResult<(ResourceArc<ShaderModuleResource>, ReflectedEntryPoint)> {
+        let cooked_shader_stage = bincode::deserialize::<CookedShaderPackage>(cooked_shader_bytes)
+            .map_err(|x| {
+                SkulpinError::ShaderLoading(format!("Failed to deserialize cooked shader: {:?}", x))
+            })?;
+
+        let shader_module = resource_context
+            .resources()
+            .get_or_create_shader_module_from_cooked_package(&cooked_shader_stage)
+            .map_err(|e| SkulpinError::ShaderLoading(e.to_string()))?;
+
+        let entry_point = cooked_shader_stage
+            .find_entry_point("main")
+            .ok_or_else(|| {
+                SkulpinError::ShaderLoading("Cooked shader has no entry point \"main\"".to_string())
+            })?
+            .clone();
+
+        Ok((shader_module, entry_point))
+    }
 }
 
 impl Drop for Renderer {
     fn drop(&mut self) {
         debug!("destroying Renderer");
-        self.graphics_queue.wait_for_queue_idle().unwrap();
+
+        // This fails if the device was lost, but the resources should still be released
+        if let Err(e) = self.graphics_queue.wait_for_queue_idle() {
+            warn!("Failed to wait for the graphics queue to be idle: {}", e);
+        }
+
+        self.destroy_swapchain();
         debug!("destroyed Renderer");
     }
 }
@@ -5,6 +5,9 @@ use ash::vk;
 use ash::version::InstanceV1_0;
 use rafx::api::vulkan::RafxRawImageVulkan;
 
+use crate::SkulpinError;
+use crate::SkulpinResult;
+
 /// Handles setting up skia to use the same vulkan instance we initialize
 pub struct VkSkiaContext {
     pub context: skia_safe::gpu::DirectContext,
@@ -14,10 +17,13 @@ impl VkSkiaContext {
     pub fn new(
         device_context: &RafxDeviceContext,
         queue: &RafxQueue,
-    ) -> Self {
+    ) -> SkulpinResult<Self> {
         use vk::Handle;
 
-        let vk_device_context = device_context.vk_device_context().unwrap();
+        let vk_device_context = device_context
+            .vk_device_context()
+            .ok_or(SkulpinError::SkiaContextCreation)?;
+        let vk_queue = queue.vk_queue().ok_or(SkulpinError::SkiaContextCreation)?;
         let entry = vk_device_context.entry();
         let instance = vk_device_context.instance();
         let physical_device = vk_device_context.physical_device();
@@ -43,7 +49,7 @@ impl VkSkiaContext {
         );
 
         let backend_context = unsafe {
-            let vk_queue_handle = *queue.vk_queue().unwrap().queue().queue().lock().unwrap();
+            let vk_queue_handle = *vk_queue.queue().queue().lock().unwrap();
             skia_safe::gpu::vk::BackendContext::new(
                 instance.handle().as_raw() as _,
                 physical_device.as_raw() as _,
@@ -56,9 +62,10 @@ impl VkSkiaContext {
             )
         };
 
-        let context = skia_safe::gpu::DirectContext::new_vulkan(&backend_context, None).unwrap();
+        let context = skia_safe::gpu::DirectContext::new_vulkan(&backend_context, None)
+            .ok_or(SkulpinError::SkiaContextCreation)?;
 
-        VkSkiaContext { context }
+        Ok(VkSkiaContext { context })
     }
 
     unsafe fn get_proc<E: ash::version::EntryV1_0>(
@@ -99,9 +106,16 @@ impl VkSkiaSurface {
         resource_manager: &ResourceManager,
         context: &mut VkSkiaContext,
         extents: RafxExtents2D,
-    ) -> RafxResult<Self> {
-        assert!(extents.width > 0);
-        assert!(extents.height > 0);
+    ) -> SkulpinResult<Self> {
+        let surface_creation_error = SkulpinError::SkiaSurfaceCreation {
+            width: extents.width,
+            height: extents.height,
+        };
+
+        if extents.width == 0 || extents.height == 0 {
+            return Err(surface_creation_error);
+        }
+
         // The "native" color type is based on platform. For example, on Windows it's BGR and on
         // MacOS it's RGB
         let color_type = skia_safe::ColorType::N32;
@@ -124,11 +138,11 @@ impl VkSkiaSurface {
             None,
             false,
         )
-        .unwrap();
+        .ok_or_else(|| surface_creation_error.clone())?;
 
         let texture = surface
             .get_backend_texture(skia_safe::surface::BackendHandleAccess::FlushRead)
-            .unwrap();
+            .ok_or_else(|| surface_creation_error.clone())?;
         let image = Self::get_image_from_skia_texture(&texture);
 
         // According to docs, kN32_SkColorType can only be kRGBA_8888_SkColorType or
@@ -150,8 +164,12 @@ impl VkSkiaSurface {
             image,
         };
 
+        let vk_device_context = device_context
+            .vk_device_context()
+            .ok_or(surface_creation_error)?;
+
         let image = rafx::api::vulkan::RafxTextureVulkan::from_existing(
-            device_context.vk_device_context().unwrap(),
+            vk_device_context,
             Some(raw_image),
             &RafxTextureDef {
                 extents: RafxExtents3D {
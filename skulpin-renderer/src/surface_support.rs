@@ -0,0 +1,54 @@
+use rafx::api::*;
+use rafx::api::ash;
+use ash::extensions::khr;
+use rafx::api::raw_window_handle::HasRawWindowHandle;
+
+use crate::PresentMode;
+use crate::SkulpinError;
+use crate::SkulpinResult;
+
+// What the device supports for presenting to a window. rafx doesn't expose the swapchain's
+// surface, so this is queried with a temporary surface. Some platforms (i.e. Android) only allow
+// one surface per window, so this must be called while the window has no swapchain. The temporary
+// surface is destroyed before returning.
+pub(crate) struct SurfaceSupport {
+    pub present_modes: Vec<PresentMode>,
+}
+
+impl SurfaceSupport {
+    pub fn query(
+        device_context: &RafxDeviceContext,
+        window: &dyn HasRawWindowHandle,
+    ) -> SkulpinResult<Self> {
+        let vk_device_context = device_context.vk_device_context().ok_or_else(|| {
+            SkulpinError::Swapchain(RafxError::StringError(
+                "The device context is not a vulkan device context".to_string(),
+            ))
+        })?;
+
+        let entry = vk_device_context.entry();
+        let instance = vk_device_context.instance();
+        let physical_device = vk_device_context.physical_device();
+        let surface_loader = khr::Surface::new(entry, instance);
+
+        unsafe {
+            let surface = ash_window::create_surface(entry, instance, window, None)
+                .map_err(|e| SkulpinError::from_swapchain_error(e.into()))?;
+
+            let present_modes =
+                surface_loader.get_physical_device_surface_present_modes(physical_device, surface);
+
+            surface_loader.destroy_surface(surface, None);
+
+            let present_modes =
+                present_modes.map_err(|e| SkulpinError::from_swapchain_error(e.into()))?;
+
+            Ok(SurfaceSupport {
+                present_modes: present_modes
+                    .into_iter()
+                    .filter_map(PresentMode::from_vk)
+                    .collect(),
+            })
+        }
+    }
+}
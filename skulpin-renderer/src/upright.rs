@@ -0,0 +1,140 @@
+//! Helpers for drawing text and images on a canvas with a Y-up coordinate system. Skia draws them
+//! mirrored along with everything else, so these flip them back to keep them readable.
+
+/// Returns true if the canvas matrix is mirrored, for example by a Y-up coordinate system. Text and
+/// images drawn on a mirrored canvas appear upside down.
+pub fn is_canvas_mirrored(canvas: &skia_safe::Canvas) -> bool {
+    let m = canvas.local_to_device_as_3x3();
+    m.scale_x() * m.scale_y() - m.skew_x() * m.skew_y() < 0.0
+}
+
+/// Draw text with its baseline starting at `origin`. If the canvas is mirrored, the text is flipped
+/// around `origin` so that it reads normally.
+pub fn draw_str_upright(
+    canvas: &mut skia_safe::Canvas,
+    text: impl AsRef<str>,
+    origin: impl Into<skia_safe::Point>,
+    font: &skia_safe::Font,
+    paint: &skia_safe::Paint,
+) {
+    draw_upright(canvas, origin.into(), |canvas| {
+        canvas.draw_str(text, (0.0, 0.0), font, paint);
+    });
+}
+
+/// Draw an image so that its top-left corner, as it appears on the screen, is at `left_top`. If the
+/// canvas is mirrored, the image is flipped around `left_top` so that it appears upright.
+pub fn draw_image_upright(
+    canvas: &mut skia_safe::Canvas,
+    image: impl AsRef<skia_safe::Image>,
+    left_top: impl Into<skia_safe::Point>,
+    paint: Option<&skia_safe::Paint>,
+) {
+    draw_upright(canvas, left_top.into(), |canvas| {
+        canvas.draw_image(image, (0.0, 0.0), paint);
+    });
+}
+
+// Calls the closure with the canvas translated to the anchor and, if necessary, flipped vertically
+fn draw_upright<F: FnOnce(&mut skia_safe::Canvas)>(
+    canvas: &mut skia_safe::Canvas,
+    anchor: skia_safe::Point,
+    f: F,
+) {
+    let is_mirrored = is_canvas_mirrored(canvas);
+
+    canvas.save();
+    canvas.translate(anchor);
+    if is_mirrored {
+        canvas.scale((1.0, -1.0));
+    }
+
+    f(canvas);
+    canvas.restore();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: skia_safe::Color = skia_safe::Color::RED;
+    const BLUE: skia_safe::Color = skia_safe::Color::BLUE;
+
+    // A 1x2 image, red on top and blue on the bottom
+    fn two_color_image() -> skia_safe::Image {
+        let mut surface = skia_safe::Surface::new_raster_n32_premul((1, 2)).unwrap();
+        let canvas = surface.canvas();
+        canvas.clear(BLUE);
+        canvas.draw_rect(
+            skia_safe::Rect::new(0.0, 0.0, 1.0, 1.0),
+            &skia_safe::Paint::new(skia_safe::Color4f::from(RED), None),
+        );
+        surface.image_snapshot()
+    }
+
+    fn pixel(
+        surface: &mut skia_safe::Surface,
+        x: i32,
+        y: i32,
+    ) -> skia_safe::Color {
+        let image_info = skia_safe::ImageInfo::new(
+            (1, 1),
+            skia_safe::ColorType::BGRA8888,
+            skia_safe::AlphaType::Premul,
+            None,
+        );
+        let mut bgra = [0u8; 4];
+        assert!(surface.read_pixels(&image_info, &mut bgra, 4, (x, y)));
+        skia_safe::Color::from_argb(bgra[3], bgra[2], bgra[1], bgra[0])
+    }
+
+    #[test]
+    fn mirrored_canvas_is_detected() {
+        let mut surface = skia_safe::Surface::new_raster_n32_premul((4, 4)).unwrap();
+        let canvas = surface.canvas();
+        assert!(!is_canvas_mirrored(canvas));
+
+        canvas.save();
+        canvas.scale((1.0, -1.0));
+        assert!(is_canvas_mirrored(canvas));
+
+        // Flipping both axes is a rotation, which is not mirrored
+        canvas.scale((-1.0, 1.0));
+        assert!(!is_canvas_mirrored(canvas));
+        canvas.restore();
+
+        canvas.scale((-1.0, 1.0));
+        assert!(is_canvas_mirrored(canvas));
+    }
+
+    #[test]
+    fn image_is_drawn_upright_on_mirrored_canvas() {
+        let image = two_color_image();
+        let mut surface = skia_safe::Surface::new_raster_n32_premul((4, 4)).unwrap();
+
+        // Y-up with (0,0) at the bottom-left
+        let canvas = surface.canvas();
+        canvas.clear(skia_safe::Color::TRANSPARENT);
+        canvas.translate((0.0, 4.0));
+        canvas.scale((1.0, -1.0));
+
+        // The top-left of the image is placed at the top-left of the surface
+        draw_image_upright(canvas, &image, (0.0, 4.0), None);
+        assert_eq!(pixel(&mut surface, 0, 0), RED);
+        assert_eq!(pixel(&mut surface, 0, 1), BLUE);
+        assert_eq!(pixel(&mut surface, 0, 2), skia_safe::Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn image_is_unchanged_on_normal_canvas() {
+        let image = two_color_image();
+        let mut surface = skia_safe::Surface::new_raster_n32_premul((4, 4)).unwrap();
+
+        let canvas = surface.canvas();
+        canvas.clear(skia_safe::Color::TRANSPARENT);
+        draw_image_upright(canvas, &image, (1.0, 1.0), None);
+        assert_eq!(pixel(&mut surface, 1, 1), RED);
+        assert_eq!(pixel(&mut surface, 1, 2), BLUE);
+        assert_eq!(pixel(&mut surface, 1, 0), skia_safe::Color::TRANSPARENT);
+    }
+}
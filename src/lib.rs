@@ -31,7 +31,20 @@ pub use skulpin_renderer::RendererBuilder;
 pub use skulpin_renderer::Renderer;
 pub use skulpin_renderer::CoordinateSystemHelper;
 pub use skulpin_renderer::CoordinateSystem;
+pub use skulpin_renderer::CoordinateSystemError;
+pub use skulpin_renderer::SkulpinError;
+pub use skulpin_renderer::SkulpinResult;
+pub use skulpin_renderer::Letterbox;
+pub use skulpin_renderer::Camera2D;
+pub use skulpin_renderer::is_canvas_mirrored;
+pub use skulpin_renderer::is_canvas_mirrored;
+pub use skulpin_renderer::draw_str_upright;
+pub use skulpin_renderer::draw_image_upright;
 pub use skulpin_renderer::ValidationMode;
+pub use skulpin_renderer::AdapterType;
+pub use skulpin_renderer::AdapterInfo;
+pub use skulpin_renderer::PresentMode;
+pub use skulpin_renderer::MAX_FRAMES_IN_FLIGHT;
 pub use skulpin_renderer::Size;
 pub use skulpin_renderer::LogicalSize;
 pub use skulpin_renderer::PhysicalSize;